@@ -0,0 +1,9 @@
+// Off-chain match simulator: `cargo run --bin simulator -- <team1.json> <team2.json> [--games N] [--seed SEED]`
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    greeter::simulator::run(std::env::args().collect());
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,449 @@
+use std::collections::BTreeMap;
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::PlayerPosition::{Center, LeftDefender, LeftWing, RightDefender, RightWing};
+use crate::team::five::{FiveIds, IceTimePriority, Tactics};
+use crate::team::numbers::{FiveNumber, GoalieNumber};
+use crate::team::players::field_player::FieldPlayerStats;
+use crate::team::players::goalie::{Goalie, GoalieStats};
+use crate::team::players::player::{GoalieSubstitution, Hand, PlayerMetadata, PlayerRole, PlayerType};
+use crate::team::team_metadata::team_metadata_to_team;
+use crate::game::autopilot::{AutopilotCondition, AutopilotDecision, AutopilotPolicy, AutopilotRule};
+use crate::user_info::USER_ID2;
+
+// The goalie is pulled this many turns before the end of the third period
+const GOALIE_PULL_TURNS: u8 = 10;
+
+const POSITIONS: [PlayerPosition; 5] = [Center, LeftWing, RightWing, LeftDefender, RightDefender];
+// Penalty kills have no left wing
+const PENALTY_KILL_POSITIONS: [PlayerPosition; 4] = [Center, RightWing, LeftDefender, RightDefender];
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BotDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl BotDifficulty {
+    /// Every stat of the generated players
+    fn get_stat_level(&self) -> u8 {
+        match self {
+            BotDifficulty::Easy => 40,
+            BotDifficulty::Medium => 60,
+            BotDifficulty::Hard => 80,
+        }
+    }
+}
+
+fn get_token_id(number: u8) -> TokenId {
+    format!("bot-{}", number)
+}
+
+fn get_field_player_metadata(number: u8, position: PlayerPosition, level: u8) -> PlayerMetadata {
+    let player_role = match position {
+        LeftDefender | RightDefender => PlayerRole::TwoWay,
+        _ => PlayerRole::Playmaker,
+    };
+
+    let field_player = FieldPlayer {
+        id: None,
+        img: None,
+        name: None,
+        teamwork: None,
+        number_of_penalty_events: None,
+        reality: false,
+        nationality: "CA".into(),
+        birthday: 0,
+        player_type: PlayerType::FieldPlayer,
+        number,
+        hand: Hand::Left,
+        player_role,
+        native_position: position,
+        stats: FieldPlayerStats {
+            acceleration: level,
+            agility: level,
+            balance: level,
+            endurance: level,
+            speed: level,
+            slap_shot_accuracy: level,
+            slap_shot_power: level,
+            wrist_shot_accuracy: level,
+            wrist_shot_power: level,
+            deking: level,
+            hand_eye: level,
+            passing: level,
+            puck_control: level,
+            aggressiveness: level,
+            body_checking: level,
+            durability: level,
+            fighting_skill: level,
+            strength: level,
+            discipline: level,
+            offensive: level,
+            poise: level,
+            morale: level,
+            defensive_awareness: level,
+            face_offs: level,
+            shot_blocking: level,
+            stick_checking: level,
+        },
+        user_id: None,
+    };
+
+    get_player_metadata(number, serde_json::to_string(&field_player).unwrap())
+}
+
+fn get_goalie_metadata(number: u8, level: u8) -> PlayerMetadata {
+    let goalie = Goalie {
+        id: None,
+        img: None,
+        name: None,
+        user_id: None,
+        reality: false,
+        nationality: "CA".into(),
+        birthday: 0,
+        player_type: PlayerType::Goalie,
+        number,
+        hand: Hand::Left,
+        player_role: PlayerRole::Hybrid,
+        stats: GoalieStats {
+            angles: level,
+            breakaway: level,
+            five_hole: level,
+            glove_side_high: level,
+            glove_side_low: level,
+            stick_side_high: level,
+            stick_side_low: level,
+            passing: level,
+            poise: level,
+            poke_check: level,
+            puck_playing: level,
+            rebound_control: level,
+            recover: level,
+            aggressiveness: level,
+            agility: level,
+            durability: level,
+            endurance: level,
+            speed: level,
+            vision: level,
+            morale: level,
+        },
+    };
+
+    get_player_metadata(number, serde_json::to_string(&goalie).unwrap())
+}
+
+fn get_player_metadata(number: u8, extra: String) -> PlayerMetadata {
+    PlayerMetadata {
+        title: Some(format!("Bot #{}", number)),
+        description: None,
+        // Generated players have no image
+        media: Some(String::new()),
+        media_hash: None,
+        issued_at: None,
+        expires_at: None,
+        starts_at: None,
+        updated_at: None,
+        extra: Some(extra),
+    }
+}
+
+fn get_five(number: FiveNumber, field_players: BTreeMap<PlayerPosition, TokenId>) -> FiveIds {
+    FiveIds {
+        field_players,
+        number,
+        ice_time_priority: IceTimePriority::Normal,
+        tactic: Tactics::Neutral,
+    }
+}
+
+/// Four fives of generated players. Power plays and penalty kills are taken from them
+pub(crate) fn generate_bot_team(difficulty: BotDifficulty) -> TeamMetadata {
+    let level = difficulty.get_stat_level();
+    let mut field_players_metadata = BTreeMap::new();
+    let mut fives = BTreeMap::new();
+
+    let five_numbers = [FiveNumber::First, FiveNumber::Second, FiveNumber::Third, FiveNumber::Fourth];
+    for (five_index, five_number) in five_numbers.iter().enumerate() {
+        let mut field_players = BTreeMap::new();
+        for (position_index, position) in POSITIONS.iter().enumerate() {
+            let number = (five_index * POSITIONS.len() + position_index + 1) as u8;
+            field_players_metadata.insert(get_token_id(number), get_field_player_metadata(number, *position, level));
+            field_players.insert(*position, get_token_id(number));
+        }
+        fives.insert(*five_number, get_five(*five_number, field_players));
+    }
+
+    let power_plays = [(FiveNumber::PowerPlay1, FiveNumber::First), (FiveNumber::PowerPlay2, FiveNumber::Second)];
+    for (power_play, five_number) in power_plays {
+        let field_players = fives[&five_number].field_players.clone();
+        fives.insert(power_play, get_five(power_play, field_players));
+    }
+
+    let penalty_kills = [(FiveNumber::PenaltyKill1, FiveNumber::Third), (FiveNumber::PenaltyKill2, FiveNumber::Fourth)];
+    for (penalty_kill, five_number) in penalty_kills {
+        let field_players = PENALTY_KILL_POSITIONS.iter()
+            .map(|position| (*position, fives[&five_number].field_players[position].clone()))
+            .collect();
+        fives.insert(penalty_kill, get_five(penalty_kill, field_players));
+    }
+
+    let mut goalies = BTreeMap::new();
+    goalies.insert(GoalieNumber::MainGoalkeeper, get_goalie_metadata(30, level));
+    goalies.insert(GoalieNumber::SubstituteGoalkeeper, get_goalie_metadata(31, level));
+
+    let fourth_five = &fives[&FiveNumber::Fourth].field_players;
+    let mut goalie_substitutions = BTreeMap::new();
+    goalie_substitutions.insert(GoalieSubstitution::GoalieSubstitution1, fourth_five[&Center].clone());
+    goalie_substitutions.insert(GoalieSubstitution::GoalieSubstitution2, fourth_five[&LeftWing].clone());
+
+    TeamMetadata {
+        fives,
+        goalies,
+        goalie_substitutions,
+        field_players_metadata,
+    }
+}
+
+/// Coach speech and timeout when behind, goalie pull at the end of a close game
+fn get_bot_autopilot_policy() -> AutopilotPolicy {
+    let rule = |conditions, decision| AutopilotRule { conditions, decision };
+    let behind = |min| AutopilotCondition::ScoreDifference { min, max: -1 };
+
+    AutopilotPolicy {
+        rules: vec![
+            rule(vec![AutopilotCondition::FromPeriod { period: 2 }, behind(i8::MIN)], AutopilotDecision::CoachSpeech),
+            rule(vec![AutopilotCondition::FromPeriod { period: 3 }, AutopilotCondition::ScoreDifference { min: i8::MIN, max: 0 }],
+                 AutopilotDecision::TakeTO),
+            rule(vec![AutopilotCondition::EndOfThirdPeriod { turns: GOALIE_PULL_TURNS }, behind(-2)],
+                 AutopilotDecision::GoalieOut { goalie_substitution: GoalieSubstitution::GoalieSubstitution1 }),
+            rule(vec![AutopilotCondition::ScoreDifference { min: 0, max: i8::MAX }], AutopilotDecision::GoalieBack),
+            rule(vec![AutopilotCondition::FromPeriod { period: 4 }], AutopilotDecision::GoalieBack),
+        ],
+    }
+}
+
+impl Hockey {
+    fn internal_get_bot_team(&self, difficulty: BotDifficulty) -> TeamMetadata {
+        self.bot_teams.get(&difficulty).unwrap_or_else(|| generate_bot_team(difficulty))
+    }
+
+    /// Nothing is paid and only the stats of the player change
+    pub(crate) fn internal_finish_bot_game(&mut self, game: &mut Game, winner_index: UserId) -> ActionData {
+        let winner_account = game.get_user_info(winner_index).account_id.clone();
+
+        game.winner_index = Some(winner_index);
+
+        if winner_account != env::current_account_id() {
+            self.internal_update_stats(&winner_account, UpdateStatsAction::AddWonFriendlyGame, None, None);
+        }
+        self.internal_stop_game(game.game_id);
+        self.finished_games.push(&game.game_id);
+
+        ActionData::GameFinished {
+            action_type: ActionTypes::GameFinished,
+            winner_account_id: winner_account,
+            reward: 0,
+            box_score: game.box_score.clone(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Starts a game against the contract. Nothing is staked
+    pub fn play_vs_bot(&mut self, difficulty: BotDifficulty) -> Promise {
+        self.assert_not_paused();
+        let account_id = predecessor_account_id();
+        self.internal_check_if_has_game_started(&account_id);
+
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
+            .with_static_gas(Gas(100_000_000_000_000))
+            .get_owner_team(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(100_000_000_000_000))
+                    .on_get_bot_game_team(account_id, difficulty)
+            )
+    }
+
+    #[private]
+    pub fn on_get_bot_game_team(&mut self,
+                                account_id: AccountId,
+                                difficulty: BotDifficulty,
+                                #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<Game> {
+        if call_result.is_err() {
+            log!("The team is incomplete");
+            return None;
+        }
+
+        let team = call_result.unwrap();
+        self.internal_check_if_has_game_started(&account_id);
+
+        let bot_id = env::current_account_id();
+        let reward = TokenBalance {
+            token_id: Some(NEAR.into()),
+            balance: 0,
+        };
+        let game_id = self.next_game_id;
+        let mut game = Game::new((team, self.internal_get_bot_team(difficulty)),
+                             account_id.clone(),
+                             bot_id.clone(),
+                             reward,
+                             &game_id,
+                             Rng::Chain(ChainRng::default()),
+                             MatchRules::default());
+        game.user1.autopilot = self.autopilot_policies.get(&account_id);
+        game.user2.autopilot = Some(get_bot_autopilot_policy());
+
+        self.internal_save_game(game.clone());
+        self.internal_add_available_game(game_id, &account_id, &bot_id);
+        self.bot_games.insert(&game_id, &difficulty);
+        self.next_game_id += 1;
+
+        self.internal_update_stats(&account_id, UpdateStatsAction::AddFriendlyGame, None, None);
+
+        Some(game)
+    }
+
+    /// Replaces the generated players of the difficulty. Generated players are used again without a team
+    pub fn set_bot_team(&mut self, difficulty: BotDifficulty, team: Option<TeamMetadata>) {
+        self.assert_owner();
+
+        match team {
+            Some(team) => {
+                assert_eq!(team.goalie_substitutions.len(), 2, "Two goalie substitutions are required");
+                team_metadata_to_team(team.clone(), USER_ID2);
+                self.bot_teams.insert(&difficulty, &team);
+            },
+            None => {
+                self.bot_teams.remove(&difficulty);
+            }
+        }
+    }
+
+    pub fn get_bot_team(&self, difficulty: BotDifficulty) -> TeamMetadata {
+        self.internal_get_bot_team(difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const DIFFICULTIES: [BotDifficulty; 3] = [BotDifficulty::Easy, BotDifficulty::Medium, BotDifficulty::Hard];
+
+    fn get_field_player(team: &TeamMetadata, token_id: &TokenId) -> FieldPlayer {
+        let extra = team.field_players_metadata[token_id].extra.clone().unwrap();
+        serde_json::from_str(&extra).unwrap()
+    }
+
+    fn get_goalie(team: &TeamMetadata, number: GoalieNumber) -> Goalie {
+        serde_json::from_str(&team.goalies[&number].extra.clone().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn every_difficulty_has_a_full_roster() {
+        for difficulty in DIFFICULTIES {
+            let team = generate_bot_team(difficulty);
+
+            assert_eq!(team.field_players_metadata.len(), 20);
+            assert_eq!(team.fives.len(), 8);
+            assert_eq!(team.goalies.len(), 2);
+            assert_eq!(team.goalie_substitutions.len(), 2);
+            for five in team.fives.values() {
+                assert!(five.field_players.values().all(|token_id| team.field_players_metadata.contains_key(token_id)));
+            }
+
+            // The roster is accepted by the engine
+            team_metadata_to_team(team, USER_ID2);
+        }
+    }
+
+    #[test]
+    fn special_teams_are_taken_from_the_fives() {
+        let team = generate_bot_team(BotDifficulty::Medium);
+
+        assert_eq!(team.fives[&FiveNumber::PowerPlay1].field_players, team.fives[&FiveNumber::First].field_players);
+        assert_eq!(team.fives[&FiveNumber::PowerPlay2].field_players, team.fives[&FiveNumber::Second].field_players);
+
+        let penalty_kill = &team.fives[&FiveNumber::PenaltyKill1].field_players;
+        assert_eq!(penalty_kill.len(), 4);
+        assert!(!penalty_kill.contains_key(&LeftWing));
+        assert_eq!(penalty_kill[&Center], team.fives[&FiveNumber::Third].field_players[&Center]);
+    }
+
+    #[test]
+    fn players_are_as_strong_as_the_difficulty() {
+        for difficulty in DIFFICULTIES {
+            let team = generate_bot_team(difficulty);
+            let level = difficulty.get_stat_level();
+
+            for (token_id, _) in team.field_players_metadata.iter() {
+                let field_player = get_field_player(&team, token_id);
+                assert_eq!((field_player.stats.speed, field_player.stats.wrist_shot_accuracy), (level, level));
+            }
+            assert_eq!(get_goalie(&team, GoalieNumber::MainGoalkeeper).stats.glove_side_high, level);
+        }
+
+        assert!(BotDifficulty::Easy.get_stat_level() < BotDifficulty::Medium.get_stat_level());
+        assert!(BotDifficulty::Medium.get_stat_level() < BotDifficulty::Hard.get_stat_level());
+    }
+
+    #[test]
+    fn owner_replaces_the_roster() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+
+        hockey.set_bot_team(BotDifficulty::Easy, Some(generate_bot_team(BotDifficulty::Hard)));
+        let team = hockey.get_bot_team(BotDifficulty::Easy);
+        assert_eq!(get_goalie(&team, GoalieNumber::MainGoalkeeper).stats.angles, BotDifficulty::Hard.get_stat_level());
+
+        hockey.set_bot_team(BotDifficulty::Easy, None);
+        let team = hockey.get_bot_team(BotDifficulty::Easy);
+        assert_eq!(get_goalie(&team, GoalieNumber::MainGoalkeeper).stats.angles, BotDifficulty::Easy.get_stat_level());
+    }
+
+    #[test]
+    #[should_panic(expected = "Two goalie substitutions are required")]
+    fn roster_without_goalie_substitutions() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.set_bot_team(BotDifficulty::Easy, Some(get_team_metadata("a")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn roster_set_by_not_owner() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_context(accounts(1));
+        hockey.set_bot_team(BotDifficulty::Easy, None);
+    }
+
+    #[test]
+    fn bot_game_is_played_to_the_end() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let team = generate_bot_team(BotDifficulty::Medium);
+
+        let mut game = hockey.on_get_bot_game_team(accounts(1), BotDifficulty::Hard, Ok(team)).unwrap();
+        while game.winner_index.is_none() {
+            // A new context for every event keeps the number of logs under the limit
+            set_context(accounts(0));
+            hockey.internal_generate_event(&mut game);
+        }
+
+        let stats = hockey.internal_get_stats(&accounts(1));
+        assert_eq!(stats.games_num, 0);
+        assert_eq!(stats.friendly_games_num, 1);
+        assert!(hockey.stats.get(&accounts(0)).is_none());
+        assert!(hockey.get_claimable(accounts(1)).is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::game::box_score::TeamBoxScore;
+use crate::team::team::Team;
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct PlayerCareerStats {
+    games_played: u64,
+    goals: u64,
+    assists: u64,
+    penalty_minutes: u64,
+
+    // Goalies
+    saves: u64,
+    shots_against: u64,
+}
+
+// (points, goals, token)
+pub type TopScorersKey = (u64, u64, TokenId);
+
+impl PlayerCareerStats {
+    /// None if the player has no points
+    fn get_top_scorers_key(&self, token_id: &TokenId) -> Option<TopScorersKey> {
+        let points = self.goals + self.assists;
+        if points > 0 {
+            Some((points, self.goals, token_id.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum VPlayerCareerStats {
+    Current(PlayerCareerStats),
+}
+
+impl From<VPlayerCareerStats> for PlayerCareerStats {
+    fn from(v_stats: VPlayerCareerStats) -> Self {
+        match v_stats {
+            VPlayerCareerStats::Current(stats) => stats,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlayerCareerStatsOutput {
+    token_id: TokenId,
+    games_played: u64,
+    goals: u64,
+    assists: u64,
+    points: u64,
+    penalty_minutes: u64,
+    saves: u64,
+    shots_against: u64,
+    // None if the player has never faced a shot in goal
+    save_percentage: Option<f32>,
+}
+
+impl PlayerCareerStatsOutput {
+    fn new(token_id: TokenId, stats: PlayerCareerStats) -> Self {
+        let save_percentage = if stats.shots_against > 0 {
+            Some(stats.saves as f32 / stats.shots_against as f32)
+        } else {
+            None
+        };
+
+        PlayerCareerStatsOutput {
+            token_id,
+            games_played: stats.games_played,
+            goals: stats.goals,
+            assists: stats.assists,
+            points: stats.goals + stats.assists,
+            penalty_minutes: stats.penalty_minutes,
+            saves: stats.saves,
+            shots_against: stats.shots_against,
+            save_percentage,
+        }
+    }
+}
+
+impl Hockey {
+    /// Folds the box score of a finished game into the career of every player of both teams
+    pub(crate) fn internal_update_player_careers(&mut self, game: &Game) {
+        for user in [&game.user1, &game.user2] {
+            let team_box_score = if game.box_score.team1.account_id == user.account_id {
+                &game.box_score.team1
+            } else {
+                &game.box_score.team2
+            };
+
+            self.internal_update_team_careers(&user.team, team_box_score);
+        }
+    }
+
+    fn internal_update_team_careers(&mut self, team: &Team, team_box_score: &TeamBoxScore) {
+        // Skaters who have been on the ice
+        for (token_id, box_score) in &team_box_score.skaters {
+            let mut career = self.internal_get_player_career(token_id);
+            let old_key = career.get_top_scorers_key(token_id);
+
+            career.games_played += 1;
+            career.goals += box_score.goals as u64;
+            career.assists += box_score.assists as u64;
+            career.penalty_minutes += box_score.penalty_minutes as u64;
+
+            self.internal_update_top_scorers(old_key, career.get_top_scorers_key(token_id));
+            self.player_careers.insert(token_id, &VPlayerCareerStats::Current(career));
+        }
+
+        // Only goalies who have been in the net, and whose token id is known
+        for (goalie_number, box_score) in &team_box_score.goalies {
+            let token_id = match team.goalies.get(goalie_number).and_then(|goalie| goalie.id.clone()) {
+                Some(token_id) => token_id,
+                None => continue,
+            };
+
+            let mut career = self.internal_get_player_career(&token_id);
+            career.games_played += 1;
+            career.saves += box_score.saves as u64;
+            career.shots_against += box_score.shots_against as u64;
+
+            self.player_careers.insert(&token_id, &VPlayerCareerStats::Current(career));
+        }
+    }
+
+    fn internal_update_top_scorers(&mut self, old_key: Option<TopScorersKey>, new_key: Option<TopScorersKey>) {
+        if old_key == new_key {
+            return;
+        }
+
+        if let Some(old_key) = old_key {
+            self.top_scorers.remove(&old_key);
+        }
+        if let Some(new_key) = new_key {
+            self.top_scorers.insert(&new_key, &());
+        }
+    }
+
+    pub(crate) fn internal_get_player_career(&self, token_id: &TokenId) -> PlayerCareerStats {
+        self.player_careers.get(token_id)
+            .map(|career| career.into())
+            .unwrap_or_default()
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn get_player_career(&self, token_id: TokenId) -> PlayerCareerStatsOutput {
+        let career = self.internal_get_player_career(&token_id);
+        PlayerCareerStatsOutput::new(token_id, career)
+    }
+
+    /// Sorted by points, then by goals. Players without points are not listed
+    pub fn get_top_scorers(&self, from_index: u64, limit: u64) -> Vec<PlayerCareerStatsOutput> {
+        self.top_scorers.iter_rev()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(|((_, _, token_id), _)| {
+                let career = self.internal_get_player_career(&token_id);
+                PlayerCareerStatsOutput::new(token_id, career)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::game::box_score::SkaterBoxScore;
+    use crate::team::numbers::GoalieNumber;
+    use crate::test_utils::{get_game, set_context};
+
+    fn get_skater(goals: u16, assists: u16) -> SkaterBoxScore {
+        SkaterBoxScore {
+            goals,
+            assists,
+            time_on_ice: 10,
+            ..SkaterBoxScore::default()
+        }
+    }
+
+    #[test]
+    fn only_skaters_on_the_ice_play_a_game() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let mut game = get_game(1);
+        game.box_score.team1.skaters.insert("a-0".into(), get_skater(1, 0));
+        game.box_score.team1.skaters.insert("a-1".into(), get_skater(0, 0));
+
+        hockey.internal_update_player_careers(&game);
+        hockey.internal_update_player_careers(&game);
+
+        let scorer = hockey.get_player_career("a-0".into());
+        assert_eq!((scorer.games_played, scorer.goals, scorer.points), (2, 2, 2));
+        assert_eq!(hockey.get_player_career("a-1".into()).games_played, 2);
+        assert_eq!(hockey.get_player_career("a-2".into()).games_played, 0);
+        assert_eq!(hockey.get_player_career("b-0".into()).games_played, 0);
+    }
+
+    #[test]
+    fn goalies_in_the_net_get_saves() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let mut game = get_game(1);
+        let goalie = game.box_score.team2.goalies.entry(GoalieNumber::MainGoalkeeper).or_default();
+        goalie.saves = 9;
+        goalie.shots_against = 10;
+        let token_id: TokenId = "b-goalie".into();
+        game.user2.team.goalies.get_mut(&GoalieNumber::MainGoalkeeper).unwrap().id = Some(token_id.clone());
+
+        hockey.internal_update_player_careers(&game);
+
+        let career = hockey.get_player_career(token_id);
+        assert_eq!((career.games_played, career.saves, career.shots_against), (1, 9, 10));
+        assert_eq!(career.save_percentage, Some(0.9));
+    }
+
+    #[test]
+    fn top_scorers_are_sorted_by_points_then_goals() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let mut game = get_game(1);
+        game.box_score.team1.skaters.insert("a-0".into(), get_skater(1, 1));
+        game.box_score.team1.skaters.insert("a-1".into(), get_skater(2, 0));
+        game.box_score.team1.skaters.insert("a-2".into(), get_skater(0, 1));
+        game.box_score.team2.skaters.insert("b-0".into(), get_skater(0, 0));
+        hockey.internal_update_player_careers(&game);
+
+        let mut game = get_game(2);
+        game.box_score.team2.skaters.insert("b-1".into(), get_skater(0, 3));
+        hockey.internal_update_player_careers(&game);
+
+        let top_scorers: Vec<TokenId> = hockey.get_top_scorers(0, 10).into_iter()
+            .map(|career| career.token_id)
+            .collect();
+        assert_eq!(top_scorers, vec!["b-1", "a-1", "a-0", "a-2"]);
+
+        let page: Vec<TokenId> = hockey.get_top_scorers(1, 2).into_iter()
+            .map(|career| career.token_id)
+            .collect();
+        assert_eq!(page, vec!["a-1", "a-0"]);
+    }
+}
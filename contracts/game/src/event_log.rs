@@ -0,0 +1,205 @@
+use crate::*;
+use near_sdk::collections::Vector;
+use near_sdk::Timestamp;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::user_info::UserId;
+
+/// Compact form of `Event` without the team snapshots
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameEvent {
+    pub(crate) player_with_puck: Option<(UserId, TokenId)>,
+    pub(crate) actions: Vec<ActionData>,
+    pub(crate) zone_number: u8,
+    pub(crate) time: Timestamp,
+}
+
+impl From<&Event> for GameEvent {
+    fn from(event: &Event) -> Self {
+        GameEvent {
+            player_with_puck: event.player_with_puck.clone(),
+            actions: event.actions.clone(),
+            zone_number: event.zone_number,
+            time: event.time,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameSummary {
+    game_id: GameId,
+
+    account_id1: AccountId,
+    score1: u8,
+
+    account_id2: AccountId,
+    score2: u8,
+
+    winner_account_id: Option<AccountId>,
+    turns: u8,
+    number_of_events: u64,
+}
+
+impl Hockey {
+    pub(crate) fn internal_save_event(&mut self, game_id: GameId, event: &Event) {
+        let mut events = self.game_events.get(&game_id).unwrap_or_else(|| {
+            Vector::new(StorageKey::GameEventsPerGame { game_id }.try_to_vec().unwrap())
+        });
+
+        events.push(&event.into());
+        self.game_events.insert(&game_id, &events);
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn get_game_events(&self, game_id: GameId, from_index: u64, limit: u64) -> Vec<GameEvent> {
+        let events = match self.game_events.get(&game_id) {
+            Some(events) => events,
+            None => return vec![],
+        };
+
+        (from_index..std::cmp::min(from_index + limit, events.len()))
+            .map(|index| events.get(index).unwrap())
+            .collect()
+    }
+
+    pub fn get_game_summary(&self, game_id: GameId) -> GameSummary {
+        let game = self.internal_get_game(&game_id);
+
+        let winner_account_id = game.winner_index
+            .map(|winner_index| game.get_user_info(winner_index).account_id.clone());
+
+        let number_of_events = self.game_events.get(&game_id)
+            .map(|events| events.len())
+            .unwrap_or(0);
+
+        GameSummary {
+            game_id,
+            account_id1: game.user1.account_id.clone(),
+            score1: game.user1.team.score,
+            account_id2: game.user2.account_id.clone(),
+            score2: game.user2.team.score,
+            winner_account_id,
+            turns: game.turns,
+            number_of_events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::manager::GameConfig;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    fn set_context_at(predecessor_account_id: AccountId, timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor_account_id)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    /// `accounts(1)` is the first user, `accounts(2)` the second
+    fn get_contract_with_game() -> (Hockey, GameId) {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let config = GameConfig {
+            deposit: Some(10),
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+
+        let game = hockey.init_game(accounts(2), accounts(1), config, (get_team_metadata("a"), get_team_metadata("b")));
+        (hockey, game.game_id)
+    }
+
+    fn get_last_actions(hockey: &Hockey, game_id: GameId) -> Vec<ActionData> {
+        let number_of_events = hockey.get_game_summary(game_id).number_of_events;
+        hockey.get_game_events(game_id, number_of_events - 1, 1).pop().unwrap().actions
+    }
+
+    #[test]
+    fn events_in_the_order_they_happened() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        set_context(accounts(1));
+        hockey.take_to(game_id);
+        set_context(accounts(2));
+        hockey.coach_speech(game_id);
+        hockey.take_to(game_id);
+        set_context(accounts(1));
+        hockey.coach_speech(game_id);
+
+        let events = hockey.get_game_events(game_id, 0, 10);
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0].actions[..], [ActionData::TakeTO { ref account_id, .. }] if *account_id == accounts(1)));
+        assert!(matches!(events[1].actions[..], [ActionData::CoachSpeech { ref account_id, .. }] if *account_id == accounts(2)));
+        assert!(matches!(events[2].actions[..], [ActionData::TakeTO { ref account_id, .. }] if *account_id == accounts(2)));
+        assert!(matches!(events[3].actions[..], [ActionData::CoachSpeech { ref account_id, .. }] if *account_id == accounts(1)));
+        assert_eq!(hockey.get_game_summary(game_id).number_of_events, 4);
+    }
+
+    #[test]
+    fn events_by_pages() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        set_context(accounts(1));
+        hockey.take_to(game_id);
+        hockey.coach_speech(game_id);
+        set_context(accounts(2));
+        hockey.take_to(game_id);
+
+        let page = hockey.get_game_events(game_id, 1, 1);
+        assert_eq!(page.len(), 1);
+        assert!(matches!(page[0].actions[..], [ActionData::CoachSpeech { .. }]));
+
+        let page = hockey.get_game_events(game_id, 1, 5);
+        assert_eq!(page.len(), 2);
+        assert!(matches!(page[1].actions[..], [ActionData::TakeTO { .. }]));
+
+        assert!(hockey.get_game_events(game_id, 3, 5).is_empty());
+        assert!(hockey.get_game_events(game_id, 0, 0).is_empty());
+        assert!(hockey.get_game_events(game_id + 1, 0, 5).is_empty());
+    }
+
+    #[test]
+    fn claimed_timeout_is_logged_after_the_last_event() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        set_context_at(accounts(1), SECOND);
+        hockey.generate_event(game_id);
+
+        let deadline = hockey.get_inactivity_deadline(game_id);
+        set_context_at(accounts(3), deadline);
+        hockey.claim_timeout(game_id);
+
+        let events = hockey.get_game_events(game_id, 0, 10);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].time, events[1].time), (SECOND, deadline));
+        assert!(matches!(events[1].actions[..], [ActionData::GameFinished { .. }]));
+    }
+
+    #[test]
+    fn resignation_is_logged() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        set_context(accounts(2));
+        hockey.resign(game_id);
+
+        let actions = get_last_actions(&hockey, game_id);
+        assert!(matches!(actions[..], [ActionData::Resign { ref account_id, .. }, ActionData::GameFinished { .. }] if *account_id == accounts(2)));
+    }
+
+    #[test]
+    fn aborted_game_is_logged() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        hockey.abort_game(game_id);
+
+        let actions = get_last_actions(&hockey, game_id);
+        assert!(matches!(actions[..], [ActionData::GameAborted { .. }]));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::*;
+use near_sdk::json_types::U128;
+use crate::bot::BotDifficulty;
 
 #[ext_contract(this_contract)]
 pub trait Callbacks {
@@ -15,6 +17,39 @@ pub trait Callbacks {
                    config: GameConfig,
                    #[callback_result] call_result: Result<TeamMetadata, PromiseError>
     ) -> bool;
+
+    fn on_get_tournament_team(&mut self,
+                              tournament_id: TournamentId,
+                              account_id: AccountId,
+                              deposit: Balance,
+                              #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> bool;
+
+    fn on_get_league_team(&mut self,
+                          league_id: LeagueId,
+                          index: usize,
+                          account_id: AccountId,
+                          #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<GameId>;
+
+    fn on_get_practice_team(&mut self,
+                            account_id: AccountId,
+                            config: GameConfig,
+                            #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<Game>;
+
+    fn on_get_bot_game_team(&mut self,
+                            account_id: AccountId,
+                            difficulty: BotDifficulty,
+                            #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<Game>;
+
+    fn on_transfer(&mut self,
+                   receiver_id: AccountId,
+                   token_id: Option<AccountId>,
+                   amount: U128,
+                   #[callback_result] call_result: Result<(), PromiseError>
+    ) -> bool;
 }
 
 #[ext_contract(ext_manage_team)]
@@ -25,4 +60,9 @@ pub trait ExtManageTeam{
     ) -> (TeamMetadata, TeamMetadata);
 
     fn get_owner_team(&self, account_id: AccountId) -> TeamMetadata;
-}
\ No newline at end of file
+}
+
+#[ext_contract(ext_ft)]
+pub trait ExtFungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
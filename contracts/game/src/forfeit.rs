@@ -0,0 +1,222 @@
+use crate::*;
+use near_sdk::Timestamp;
+use crate::manager::UpdateStatsAction;
+
+impl Hockey {
+    /// Nobody can claim the game before the next event is allowed and the inactivity timeout has passed.
+    /// A league game can also be claimed at the deadline of its round
+    pub(crate) fn internal_get_inactivity_deadline(&self, game: &Game) -> Timestamp {
+        let deadline = game.last_event_generation_time
+            + game.event_generation_delay
+            + game.rules.inactivity_timeout * SECOND;
+
+        match self.league_games.get(&game.game_id) {
+            Some(league_game) => deadline.min(self.internal_get_league_deadline(&league_game)),
+            None => deadline,
+        }
+    }
+
+    fn internal_get_user_id(&self, game: &Game, account_id: &AccountId) -> UserId {
+        if game.user1.account_id == *account_id {
+            game.user1.user_id
+        } else if game.user2.account_id == *account_id {
+            game.user2.user_id
+        } else {
+            panic!("You are not a player of this game")
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Ends an abandoned game. Can be called by anyone once the inactivity deadline is reached.
+    /// The player who generated the last event wins, a game nobody has played is aborted
+    pub fn claim_timeout(&mut self, game_id: GameId) -> Event {
+        let mut game = self.internal_get_game(&game_id);
+        game.assert_not_finished();
+
+        let time = env::block_timestamp();
+        assert!(time >= self.internal_get_inactivity_deadline(&game), "The game is still active");
+
+        let winner_index = match game.last_active_account {
+            Some(ref account_id) => self.internal_get_user_id(&game, account_id),
+            None => return self.internal_abort_game(game),
+        };
+
+        let idle_account = game.get_opponent_info(winner_index).account_id.clone();
+        self.internal_update_stats(&idle_account, UpdateStatsAction::AddPenaltyGame, None, None);
+
+        game.last_event_generation_time = time;
+        let mut actions = vec![self.internal_finish_game(&mut game, winner_index)];
+        let generated_event = game.generate_event(&mut actions);
+
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &generated_event);
+
+        generated_event
+    }
+
+    /// The opponent of the caller wins the game
+    pub fn resign(&mut self, game_id: GameId) -> Event {
+        let account_id = predecessor_account_id();
+        let mut game = self.internal_get_game(&game_id);
+        game.assert_not_finished();
+
+        let user_id = self.internal_get_user_id(&game, &account_id);
+        let opponent_id = game.get_opponent_info(user_id).user_id;
+
+        self.internal_update_stats(&account_id, UpdateStatsAction::AddPenaltyGame, None, None);
+
+        game.last_event_generation_time = env::block_timestamp();
+        let mut actions = vec![ActionData::Resign {
+            action_type: ActionTypes::Resign,
+            account_id,
+        }];
+        actions.push(self.internal_finish_game(&mut game, opponent_id));
+
+        let generated_event = game.generate_event(&mut actions);
+
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &generated_event);
+
+        generated_event
+    }
+
+    /// Timestamp in nanoseconds from which `claim_timeout` can be called
+    pub fn get_inactivity_deadline(&self, game_id: GameId) -> Timestamp {
+        let game = self.internal_get_game(&game_id);
+        self.internal_get_inactivity_deadline(&game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::bot::{generate_bot_team, BotDifficulty};
+    use crate::manager::GameConfig;
+    use crate::user_info::{USER_ID1, USER_ID2};
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const DEPOSIT: Balance = 10;
+
+    fn set_context_at(predecessor_account_id: AccountId, timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor_account_id)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    /// `accounts(1)` is the first user, `accounts(2)` the second. Both stake `DEPOSIT`
+    fn get_contract_with_game() -> (Hockey, GameId) {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let config = GameConfig {
+            deposit: Some(DEPOSIT),
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+
+        let game = hockey.init_game(accounts(2), accounts(1), config, (get_team_metadata("a"), get_team_metadata("b")));
+        (hockey, game.game_id)
+    }
+
+    #[test]
+    fn stalled_game_is_awarded_to_the_active_player() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        set_context_at(accounts(1), SECOND);
+        hockey.generate_event(game_id);
+
+        set_context_at(accounts(3), hockey.get_inactivity_deadline(game_id));
+        hockey.claim_timeout(game_id);
+
+        let game = hockey.internal_get_game(&game_id);
+        assert_eq!(game.winner_index, Some(USER_ID1));
+        assert!(game.turns <= 1);
+        assert!(hockey.available_games.get(&game_id).is_none());
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(2 * DEPOSIT - 2))]);
+        assert_eq!(hockey.internal_get_stats(&accounts(2)).penalties_num, 1);
+    }
+
+    #[test]
+    fn active_player_wins_after_the_sides_are_swapped() {
+        let (mut hockey, _) = get_contract_with_game();
+        let config = GameConfig {
+            deposit: Some(DEPOSIT),
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+        // Full rosters, so lines can be changed during the period
+        let teams = (generate_bot_team(BotDifficulty::Medium), generate_bot_team(BotDifficulty::Medium));
+        let game_id = hockey.init_game(accounts(4), accounts(1), config, teams).game_id;
+
+        // The last event ends the first period
+        let mut time = 0;
+        while hockey.internal_get_game(&game_id).user1.account_id == accounts(1) {
+            time += 10 * SECOND;
+            set_context_at(accounts(1), time);
+            hockey.generate_event(game_id);
+        }
+
+        set_context_at(accounts(3), hockey.get_inactivity_deadline(game_id));
+        hockey.claim_timeout(game_id);
+
+        let game = hockey.internal_get_game(&game_id);
+        assert_eq!(game.get_user_info(game.winner_index.unwrap()).account_id, accounts(1));
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(2 * DEPOSIT - 2))]);
+        assert_eq!(hockey.internal_get_stats(&accounts(4)).penalties_num, 1);
+        assert_eq!(hockey.internal_get_stats(&accounts(1)).penalties_num, 0);
+    }
+
+    #[test]
+    fn unplayed_game_is_aborted() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        set_context_at(accounts(3), hockey.get_inactivity_deadline(game_id));
+        hockey.claim_timeout(game_id);
+
+        let game = hockey.internal_get_game(&game_id);
+        assert!(game.is_aborted);
+        assert!(game.winner_index.is_none());
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(DEPOSIT))]);
+        assert_eq!(hockey.get_claimable(accounts(2)), vec![(None, U128::from(DEPOSIT))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The game is still active")]
+    fn claim_before_deadline() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        set_context_at(accounts(3), hockey.get_inactivity_deadline(game_id) - 1);
+        hockey.claim_timeout(game_id);
+    }
+
+    #[test]
+    fn resign_gives_the_game_to_the_opponent() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        set_context(accounts(1));
+        hockey.resign(game_id);
+
+        let game = hockey.internal_get_game(&game_id);
+        assert_eq!(game.winner_index, Some(USER_ID2));
+        assert_eq!(hockey.get_claimable(accounts(2)), vec![(None, U128::from(2 * DEPOSIT - 2))]);
+        assert_eq!(hockey.internal_get_stats(&accounts(1)).penalties_num, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "You are not a player of this game")]
+    fn resign_by_outsider() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        set_context(accounts(3));
+        hockey.resign(game_id);
+    }
+}
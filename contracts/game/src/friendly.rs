@@ -0,0 +1,270 @@
+use crate::*;
+use crate::manager::GameConfigOutput;
+
+impl Hockey {
+    /// Friendly games have no reward and do not change ratings, careers and leaderboards
+    pub(crate) fn internal_finish_friendly_game(&mut self, game: &mut Game, winner_index: UserId) -> ActionData {
+        let winner_account = game.get_user_info(winner_index).account_id.clone();
+
+        game.winner_index = Some(winner_index);
+
+        self.internal_update_stats(&winner_account, UpdateStatsAction::AddWonFriendlyGame, None, None);
+        self.internal_record_game_results(game, winner_index, 0);
+        self.internal_stop_game(game.game_id);
+        self.finished_games.push(&game.game_id);
+
+        ActionData::GameFinished {
+            action_type: ActionTypes::GameFinished,
+            winner_account_id: winner_account,
+            reward: 0,
+            box_score: game.box_score.clone(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Waits for a practice game with the same rules. Nothing is staked
+    pub fn join_practice_queue(&mut self, rules: Option<VMatchRules>) -> Promise {
+        self.assert_not_paused();
+        let account_id = predecessor_account_id();
+        assert!(self.practice_queue.get(&account_id).is_none(), "Already in the practice queue");
+
+        let config = GameConfig {
+            deposit: Some(0),
+            opponent_id: None,
+            rules,
+            waiting_since: None,
+            token_id: None,
+        };
+        config.get_rules().assert_valid();
+
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
+            .with_static_gas(Gas(100_000_000_000_000))
+            .get_owner_team(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(100_000_000_000_000))
+                    .on_get_practice_team(account_id, config)
+            )
+    }
+
+    #[private]
+    pub fn on_get_practice_team(&mut self,
+                                account_id: AccountId,
+                                config: GameConfig,
+                                #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<Game> {
+        if call_result.is_err() {
+            log!("The team is incomplete");
+            return None;
+        }
+
+        let team = call_result.unwrap();
+        assert!(self.practice_queue.get(&account_id).is_none(), "Already in the practice queue");
+        self.internal_check_if_has_game_started(&account_id);
+
+        let rules = config.get_rules();
+        let opponent_id = self.practice_queue.iter()
+            .map(|(opponent_id, opponent_config)| (opponent_id, GameConfig::from(opponent_config)))
+            .find(|(_, opponent_config)| opponent_config.get_rules() == rules)
+            .map(|(opponent_id, _)| opponent_id);
+
+        match opponent_id {
+            None => {
+                self.practice_queue.insert(&account_id, &VGameConfig::Current(GameConfig {
+                    waiting_since: Some(env::block_timestamp()),
+                    ..config
+                }));
+                self.practice_teams.insert(&account_id, &team);
+                None
+            },
+            Some(opponent_id) => {
+                self.practice_queue.remove(&opponent_id);
+                let opponent_team = self.practice_teams.remove(&opponent_id).expect("Team not found");
+
+                Some(self.init_game(opponent_id, account_id, config, (team, opponent_team)))
+            }
+        }
+    }
+
+    pub fn leave_practice_queue(&mut self) {
+        let account_id = predecessor_account_id();
+        self.practice_queue.remove(&account_id).expect("Not in the practice queue");
+        self.practice_teams.remove(&account_id);
+    }
+
+    pub fn get_practice_queue(&self, from_index: u64, limit: u64) -> Vec<(AccountId, GameConfigOutput)> {
+        self.get_available_players(from_index, limit, &self.practice_queue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    fn set_context_with_deposit(predecessor_account_id: AccountId, deposit: Balance) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor_account_id)
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    /// `accounts(1)` challenges `accounts(2)` to a friendly game with short periods
+    fn get_contract_with_challenge() -> Hockey {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        for index in 1..=2 {
+            set_context(accounts(index));
+            hockey.register_account();
+        }
+
+        set_context(accounts(1));
+        let rules = MatchRules { first_period: 5, second_period: 10, third_period: 15, overtime_period: 20, ..MatchRules::default() };
+        hockey.send_request_play(accounts(2), Some(VMatchRules::Current(rules)));
+
+        hockey
+    }
+
+    fn assert_no_challenge(hockey: &Hockey) {
+        let account = hockey.internal_get_account(&accounts(1)).unwrap();
+        let friend = hockey.internal_get_account(&accounts(2)).unwrap();
+        assert!(account.sent_requests_play.is_empty());
+        assert!(friend.requests_play_received.is_empty());
+        assert!(hockey.play_request_rules.get(&(accounts(1), accounts(2))).is_none());
+    }
+
+    #[test]
+    fn friendly_game_keeps_rating() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let config = GameConfig {
+            deposit: Some(0),
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+
+        let mut game = hockey.init_game(accounts(1), accounts(2), config, (get_team_metadata("a"), get_team_metadata("b")));
+        let winner_id = game.user1.account_id.clone();
+        let rating = hockey.internal_get_stats(&winner_id).rating;
+        hockey.internal_finish_game(&mut game, 1);
+
+        let stats = hockey.internal_get_stats(&winner_id);
+        assert_eq!(stats.rating, rating);
+        assert_eq!(stats.games_num, 0);
+        assert_eq!(stats.friendly_games_num, 1);
+        assert_eq!(stats.friendly_victories_num, 1);
+        assert!(hockey.get_claimable(winner_id).is_empty());
+    }
+
+    #[test]
+    fn declined_challenge_is_removed() {
+        let mut hockey = get_contract_with_challenge();
+
+        set_context(accounts(2));
+        hockey.decline_request_play(accounts(1));
+
+        assert_no_challenge(&hockey);
+        assert!(hockey.get_claimable(accounts(1)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Request to play not found")]
+    fn declined_challenge_cannot_be_accepted() {
+        let mut hockey = get_contract_with_challenge();
+
+        set_context(accounts(2));
+        hockey.decline_request_play(accounts(1));
+        hockey.accept_request_play(accounts(1));
+    }
+
+    #[test]
+    fn challenge_is_withdrawn_by_the_sender() {
+        let mut hockey = get_contract_with_challenge();
+
+        set_context(accounts(1));
+        hockey.decline_request_play(accounts(2));
+
+        assert_no_challenge(&hockey);
+    }
+
+    #[test]
+    fn declined_stake_is_refunded() {
+        let mut hockey = get_contract_with_challenge();
+        set_context_with_deposit(accounts(1), 10);
+        hockey.send_request_play(accounts(2), None);
+
+        set_context(accounts(2));
+        hockey.decline_request_play(accounts(1));
+
+        assert_no_challenge(&hockey);
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(10))]);
+    }
+
+    #[test]
+    fn new_request_returns_the_previous_stake() {
+        let mut hockey = get_contract_with_challenge();
+        set_context_with_deposit(accounts(1), 10);
+        hockey.send_request_play(accounts(2), None);
+        hockey.internal_send_request_play(accounts(1), accounts(2), None, 5, Some(accounts(3)));
+        set_context_with_deposit(accounts(1), 20);
+        hockey.send_request_play(accounts(2), None);
+
+        let mut claimable = hockey.get_claimable(accounts(1));
+        claimable.sort();
+        assert_eq!(claimable, vec![(None, U128::from(10)), (Some(accounts(3)), U128::from(5))]);
+        assert_eq!(hockey.internal_get_account(&accounts(2)).unwrap().requests_play_received.get(&accounts(1)), Some(20));
+    }
+
+    #[test]
+    fn accepted_challenge_without_teams_lapses() {
+        let mut hockey = get_contract_with_challenge();
+
+        set_context(accounts(2));
+        hockey.accept_request_play(accounts(1));
+        assert_no_challenge(&hockey);
+
+        set_context(accounts(0));
+        let config = GameConfig {
+            deposit: Some(0),
+            opponent_id: Some(accounts(1)),
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+        assert!(hockey.on_get_teams(accounts(1), accounts(2), config, Err(PromiseError::Failed)).is_none());
+
+        assert!(hockey.get_claimable(accounts(1)).is_empty());
+        assert!(hockey.get_claimable(accounts(2)).is_empty());
+        assert_eq!(hockey.get_next_game_id(), 0);
+    }
+
+    #[test]
+    fn accepted_challenge_is_a_friendly_game() {
+        let mut hockey = get_contract_with_challenge();
+
+        set_context(accounts(2));
+        hockey.accept_request_play(accounts(1));
+
+        set_context(accounts(0));
+        let config = GameConfig {
+            deposit: Some(0),
+            opponent_id: Some(accounts(1)),
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+        let game = hockey.on_get_teams(accounts(1), accounts(2), config, Ok((get_team_metadata("a"), get_team_metadata("b"))))
+            .expect("Game not started");
+
+        assert!(hockey.friendly_games.contains(&game.game_id));
+        assert_eq!(game.reward.balance, 0);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::PromiseOrValue;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::external::{ext_ft, this_contract};
+use crate::game::match_rules::VMatchRules;
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_ON_TRANSFER: Gas = Gas(10_000_000_000_000);
+
+/// `msg` of `ft_transfer_call`. The transferred amount is the stake
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FtMessage {
+    MakeAvailable { config: GameConfig },
+    SendRequestPlay { friend_id: AccountId, rules: Option<VMatchRules> },
+    AcceptRequestPlay { friend_id: AccountId },
+}
+
+impl Hockey {
+    /// Sends NEAR, or the fungible token if `token_id` is set. Failed transfers are credited to the receiver
+    pub(crate) fn internal_transfer(&self, token_id: &Option<AccountId>, receiver_id: &AccountId, amount: Balance) -> Promise {
+        let transfer = match token_id {
+            None => Promise::new(receiver_id.clone()).transfer(amount),
+            Some(token_id) => ext_ft::ext(token_id.clone())
+                .with_attached_deposit(ONE_YOCTO)
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(receiver_id.clone(), U128::from(amount), None),
+        };
+
+        transfer.then(
+            this_contract::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_TRANSFER)
+                .on_transfer(receiver_id.clone(), token_id.clone(), U128::from(amount))
+        )
+    }
+
+    pub(crate) fn internal_assert_whitelisted_token(&self, token_id: &AccountId) {
+        assert!(self.whitelisted_tokens.contains(token_id), "Token is not whitelisted");
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Stakes fungible tokens for a game. The whole amount is always used
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let token_id = predecessor_account_id();
+        self.internal_assert_whitelisted_token(&token_id);
+        assert!(amount.0 > 0, "Stake must be positive");
+
+        let message: FtMessage = serde_json::from_str(&msg).expect("Wrong message");
+        match message {
+            FtMessage::MakeAvailable { config } => {
+                self.internal_make_available(sender_id, amount.0, GameConfig { token_id: Some(token_id), ..config });
+            },
+            FtMessage::SendRequestPlay { friend_id, rules } => {
+                self.internal_send_request_play(sender_id, friend_id, rules, amount.0, Some(token_id));
+            },
+            FtMessage::AcceptRequestPlay { friend_id } => {
+                self.internal_accept_request_play(sender_id, friend_id, amount.0, Some(token_id));
+            },
+        }
+
+        PromiseOrValue::Value(U128::from(0))
+    }
+
+    pub fn add_whitelisted_token(&mut self, token_id: AccountId) {
+        self.assert_owner();
+        self.whitelisted_tokens.insert(&token_id);
+    }
+
+    pub fn remove_whitelisted_token(&mut self, token_id: AccountId) {
+        self.assert_owner();
+        self.whitelisted_tokens.remove(&token_id);
+    }
+
+    pub fn get_whitelisted_tokens(&self) -> Vec<AccountId> {
+        self.whitelisted_tokens.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const MAKE_AVAILABLE: &str = r#"{"MakeAvailable":{"config":{}}}"#;
+
+    /// `accounts(3)` is a whitelisted token
+    fn get_contract() -> Hockey {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.add_whitelisted_token(accounts(3));
+        hockey
+    }
+
+    fn get_config(token_id: Option<AccountId>) -> GameConfig {
+        GameConfig {
+            deposit: None,
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id,
+        }
+    }
+
+    #[test]
+    fn whole_transfer_is_staked() {
+        let mut hockey = get_contract();
+
+        set_context(accounts(3));
+        match hockey.ft_on_transfer(accounts(1), U128::from(10), MAKE_AVAILABLE.to_string()) {
+            PromiseOrValue::Value(unused) => assert_eq!(unused, U128::from(0)),
+            PromiseOrValue::Promise(_) => panic!("Nothing must be returned to the sender"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is not whitelisted")]
+    fn stake_in_unknown_token() {
+        let mut hockey = get_contract();
+
+        set_context(accounts(4));
+        hockey.ft_on_transfer(accounts(1), U128::from(10), MAKE_AVAILABLE.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Stake must be positive")]
+    fn zero_stake() {
+        let mut hockey = get_contract();
+
+        set_context(accounts(3));
+        hockey.ft_on_transfer(accounts(1), U128::from(0), MAKE_AVAILABLE.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong message")]
+    fn unknown_message() {
+        let mut hockey = get_contract();
+
+        set_context(accounts(3));
+        hockey.ft_on_transfer(accounts(1), U128::from(10), "{}".to_string());
+    }
+
+    #[test]
+    fn token_stakes_are_matched_and_paid_in_the_token() {
+        let mut hockey = get_contract();
+
+        assert!(hockey.on_get_team(accounts(1), 10, get_config(Some(accounts(3))), Ok(get_team_metadata("a"))).is_none());
+        // The same stake in NEAR is not an opponent
+        assert!(hockey.on_get_team(accounts(2), 10, get_config(None), Ok(get_team_metadata("b"))).is_none());
+
+        let mut game = hockey.on_get_team(accounts(4), 10, get_config(Some(accounts(3))), Ok(get_team_metadata("c")))
+            .expect("Game not started");
+        assert_eq!(game.reward.get_ft_token_id(), Some(accounts(3)));
+        assert_eq!(game.reward.balance, 20);
+
+        hockey.internal_finish_game(&mut game, 1);
+
+        let winner_id = game.user1.account_id.clone();
+        assert!(winner_id == accounts(1) || winner_id == accounts(4));
+        assert_eq!(hockey.get_claimable(winner_id), vec![(Some(accounts(3)), U128::from(18))]);
+        assert_eq!(hockey.ft_service_fees.get(&accounts(3)), Some(2));
+        assert_eq!(hockey.service_fee, 0);
+    }
+
+    #[test]
+    fn failed_team_request_refunds_the_token() {
+        let mut hockey = get_contract();
+
+        assert!(hockey.on_get_team(accounts(1), 10, get_config(Some(accounts(3))), Err(PromiseError::Failed)).is_none());
+
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(Some(accounts(3)), U128::from(10))]);
+    }
+}
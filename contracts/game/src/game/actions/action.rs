@@ -1,16 +1,20 @@
 use crate::team::players::player::{PlayerRole};
+use std::io::{Error, Write};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{AccountId, Balance, log};
 use crate::team::players::player::PlayerRole::*;
 
 use near_sdk::serde::{Deserialize, Serialize};
 use crate::game::actions::shot::ShotAction;
+use crate::game::actions::shot_type::{ShotType, ShotZone};
 use crate::game::actions::dangle::DangleAction;
 use crate::game::actions::dump::DumpAction;
+use crate::game::actions::hit::{HitAction, PokeCheckAction};
 use crate::game::actions::move_action::MoveAction;
 use crate::game::actions::pass::PassAction;
 use crate::game::actions::random_actions::{BigPenalty, Fight, Giveaway, NetOff, PuckOut, RandomAction, SmallPenalty, Takeaway};
 
+use crate::game::box_score::BoxScore;
 use crate::game::game::{Game};
 use crate::team::five::{ActiveFive, FiveIds, Tactics};
 use crate::team::numbers::{FiveNumber};
@@ -60,6 +64,10 @@ pub enum ActionTypes {
     GoalieBack,
     PenaltyShot,
     EndedPenalty,
+    Resign,
+    GameAborted,
+    Shootout,
+    ShootoutAttempt,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -84,6 +92,7 @@ pub enum ActionData {
         action_type: ActionTypes,
         winner_account_id: AccountId,
         reward: Balance,
+        box_score: BoxScore,
     },
     Pass {
         action_type: ActionTypes,
@@ -115,6 +124,9 @@ pub enum ActionData {
 
         player_number: u8,
         player_position: PlayerPosition,
+
+        shot_type: ShotType,
+        shot_zone: ShotZone,
     },
     ShotBlocked {
         action_type: ActionTypes,
@@ -144,6 +156,9 @@ pub enum ActionData {
         // The player who gave the assist
         player_name2: Option<String>,
         player_number2: Option<u8>,
+
+        shot_type: ShotType,
+        shot_zone: ShotZone,
     },
     Save {
         action_type: ActionTypes,
@@ -151,6 +166,9 @@ pub enum ActionData {
 
         // Goalie
         goalie_number: u8,
+
+        shot_type: ShotType,
+        shot_zone: ShotZone,
     },
     Rebound {
         action_type: ActionTypes,
@@ -359,6 +377,133 @@ pub enum ActionData {
         account_id: AccountId,
         player_number: u8,
     },
+    Resign {
+        action_type: ActionTypes,
+        // The account who gave up the game
+        account_id: AccountId,
+    },
+    GameAborted {
+        action_type: ActionTypes,
+    },
+    Shootout {
+        action_type: ActionTypes
+    },
+    ShootoutAttempt {
+        action_type: ActionTypes,
+        account_id: AccountId,
+
+        player_name: String,
+        player_img: String,
+        player_number: u8,
+
+        // Opponent goalie
+        goalie_number: u8,
+
+        round: u8,
+        is_goal: bool,
+    },
+}
+
+// Variants of the first version that had fewer fields
+const GAME_FINISHED_V1: u8 = 4;
+const SHOT_V1: u8 = 7;
+const GOAL_V1: u8 = 10;
+const SAVE_V1: u8 = 11;
+
+/// `ActionData` as stored in the games of the first version. Shots, goals and saves had no type
+/// and zone and are read as wrist shots at the five-hole. Finished games had no box score
+pub struct ActionDataV1(pub(crate) ActionData);
+
+impl BorshDeserialize for ActionDataV1 {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, Error> {
+        let index = <u8 as BorshDeserialize>::deserialize(buf)?;
+        let action = match index {
+            GAME_FINISHED_V1 => {
+                let action_type = <ActionTypes as BorshDeserialize>::deserialize(buf)?;
+                let winner_account_id = <AccountId as BorshDeserialize>::deserialize(buf)?;
+                ActionData::GameFinished {
+                    action_type,
+                    reward: <Balance as BorshDeserialize>::deserialize(buf)?,
+                    box_score: BoxScore::new(winner_account_id.clone(), winner_account_id.clone()),
+                    winner_account_id,
+                }
+            },
+            SHOT_V1 => ActionData::Shot {
+                action_type: <ActionTypes as BorshDeserialize>::deserialize(buf)?,
+                account_id: <AccountId as BorshDeserialize>::deserialize(buf)?,
+                player_number: <u8 as BorshDeserialize>::deserialize(buf)?,
+                player_position: <PlayerPosition as BorshDeserialize>::deserialize(buf)?,
+                shot_type: ShotType::Wrist,
+                shot_zone: ShotZone::FiveHole,
+            },
+            GOAL_V1 => ActionData::Goal {
+                action_type: <ActionTypes as BorshDeserialize>::deserialize(buf)?,
+                account_id: <AccountId as BorshDeserialize>::deserialize(buf)?,
+                player_name1: <String as BorshDeserialize>::deserialize(buf)?,
+                player_img: <String as BorshDeserialize>::deserialize(buf)?,
+                player_number1: <u8 as BorshDeserialize>::deserialize(buf)?,
+                player_name2: <Option<String> as BorshDeserialize>::deserialize(buf)?,
+                player_number2: <Option<u8> as BorshDeserialize>::deserialize(buf)?,
+                shot_type: ShotType::Wrist,
+                shot_zone: ShotZone::FiveHole,
+            },
+            SAVE_V1 => ActionData::Save {
+                action_type: <ActionTypes as BorshDeserialize>::deserialize(buf)?,
+                account_id: <AccountId as BorshDeserialize>::deserialize(buf)?,
+                goalie_number: <u8 as BorshDeserialize>::deserialize(buf)?,
+                shot_type: ShotType::Wrist,
+                shot_zone: ShotZone::FiveHole,
+            },
+            _ => {
+                // The other variants have not changed
+                let mut bytes = vec![index];
+                bytes.extend_from_slice(buf);
+                let mut rest = &bytes[..];
+                let action = <ActionData as BorshDeserialize>::deserialize(&mut rest)?;
+                *buf = &buf[buf.len() - rest.len()..];
+                action
+            },
+        };
+
+        Ok(ActionDataV1(action))
+    }
+}
+
+impl BorshSerialize for ActionDataV1 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        match &self.0 {
+            ActionData::GameFinished { action_type, winner_account_id, reward, .. } => {
+                BorshSerialize::serialize(&GAME_FINISHED_V1, writer)?;
+                BorshSerialize::serialize(action_type, writer)?;
+                BorshSerialize::serialize(winner_account_id, writer)?;
+                BorshSerialize::serialize(reward, writer)
+            },
+            ActionData::Shot { action_type, account_id, player_number, player_position, .. } => {
+                BorshSerialize::serialize(&SHOT_V1, writer)?;
+                BorshSerialize::serialize(action_type, writer)?;
+                BorshSerialize::serialize(account_id, writer)?;
+                BorshSerialize::serialize(player_number, writer)?;
+                BorshSerialize::serialize(player_position, writer)
+            },
+            ActionData::Goal { action_type, account_id, player_name1, player_img, player_number1, player_name2, player_number2, .. } => {
+                BorshSerialize::serialize(&GOAL_V1, writer)?;
+                BorshSerialize::serialize(action_type, writer)?;
+                BorshSerialize::serialize(account_id, writer)?;
+                BorshSerialize::serialize(player_name1, writer)?;
+                BorshSerialize::serialize(player_img, writer)?;
+                BorshSerialize::serialize(player_number1, writer)?;
+                BorshSerialize::serialize(player_name2, writer)?;
+                BorshSerialize::serialize(player_number2, writer)
+            },
+            ActionData::Save { action_type, account_id, goalie_number, .. } => {
+                BorshSerialize::serialize(&SAVE_V1, writer)?;
+                BorshSerialize::serialize(action_type, writer)?;
+                BorshSerialize::serialize(account_id, writer)?;
+                BorshSerialize::serialize(goalie_number, writer)
+            },
+            action => BorshSerialize::serialize(action, writer),
+        }
+    }
 }
 
 pub trait DoAction {
@@ -380,10 +525,16 @@ impl Action {
         let mut random_actions: Vec<Box<dyn RandomAction>> = vec![
             Box::new(Giveaway),
             Box::new(Takeaway),
+            Box::new(HitAction),
+            Box::new(PokeCheckAction),
             Box::new(PuckOut),
-            Box::new(Fight),
         ];
 
+        // No fights and penalties in overtime
+        if !game.is_overtime() {
+            random_actions.push(Box::new(Fight));
+        }
+
         if game.zone_number != 2 {
             random_actions.push(Box::new(NetOff));
         }
@@ -391,7 +542,7 @@ impl Action {
         let number_of_penalty_players1 = game.user1.team.get_number_of_penalty_players();
         let number_of_penalty_players2 = game.user2.team.get_number_of_penalty_players();
 
-        if number_of_penalty_players1 < 2 && number_of_penalty_players2 < 2 {
+        if number_of_penalty_players1 < 2 && number_of_penalty_players2 < 2 && !game.is_overtime() {
             random_actions.push(Box::new(BigPenalty));
             random_actions.push(Box::new(SmallPenalty));
         }
@@ -420,12 +571,12 @@ impl Action {
         let player_with_puck_role = user.team.get_field_player(&user_player_id.1).player_role;
 
         log!("{} {}", user_player_id.0, user_player_id.1);
-        let action = self.get_action(is_attack_zone, player_with_puck_role, active_five);
+        let action = self.get_action(game, is_attack_zone, player_with_puck_role, active_five);
 
         action.do_action(game)
     }
 
-    fn get_action(&self, is_attack_zone: bool, role: PlayerRole, active_five: &ActiveFive) -> Box<dyn DoAction> {
+    fn get_action(&self, game: &Game, is_attack_zone: bool, role: PlayerRole, active_five: &ActiveFive) -> Box<dyn DoAction> {
         let actions = self.get_probability_of_actions(role, active_five);
 
         let mut percent = 0.0;
@@ -436,7 +587,7 @@ impl Action {
         }
         percent = 100.0 / percent;
 
-        let rnd = Game::get_random_in_range(1, 101, 0) as f32;
+        let rnd = game.get_random_in_range(1, 101) as f32;
 
         return if !is_attack_zone && percent * action_probability[0] >= rnd {
             log!("Dump");
@@ -36,7 +36,7 @@ impl DoAction for DangleAction {
             opponent_number: opponent.1.number,
         }];
 
-        let rnd_offside = Game::get_random_in_range(1, 100, 21);
+        let rnd_offside = game.get_random_in_range(1, 100);
         if rnd_offside <= 15 {
             actions.push(Offside {
                 action_type: ActionTypes::Offside,
@@ -49,7 +49,7 @@ impl DoAction for DangleAction {
             return actions;
         }
 
-        if has_won(player_stat, opponent_stat) {
+        if has_won(game, player_stat, opponent_stat) {
             game.zone_number = new_zone_number;
         } else {
             let opponent_user = game.get_opponent_info(user.user_id);
@@ -42,7 +42,7 @@ impl DumpAction {
         let active_five = user.team.get_active_five();
 
         let player_position = game.get_player_pos(&user_player_id.1, user_player_id.0);
-        let position_to_dump = self.get_random_pos_to_dump(player_position, &active_five);
+        let position_to_dump = self.get_random_pos_to_dump(game, player_position, &active_five);
 
         let player_id_to_dump = game.get_field_player_id_by_pos(
             position_to_dump, user.user_id.clone());
@@ -91,7 +91,7 @@ impl DumpAction {
             _ => {
                 match user.team.get_field_player_pos(&player_with_puck_id.1) {
                     PlayerPosition::LeftDefender | PlayerPosition::RightDefender => {
-                        let rnd = Game::get_random_in_range(1, 100, 3);
+                        let rnd = game.get_random_in_range(1, 100);
 
                         if ICING_PROBABILITY >= rnd {
                             let player_with_puck = user.team.get_field_player(&player_with_puck_id.1);
@@ -116,6 +116,7 @@ impl DumpAction {
 
     fn get_random_pos_to_dump(
         &self,
+        game: &Game,
         player_position: &PlayerPosition,
         five: &ActiveFive
     ) -> &PlayerPosition {
@@ -128,7 +129,7 @@ impl DumpAction {
             }
         }
 
-        let rnd = Game::get_random_in_range(1, 100, 4);
+        let rnd = game.get_random_in_range(1, 100);
 
         return if POSITION_PROBABILITY >= rnd && PlayerPosition::LeftWing != *player_position {
             &PlayerPosition::LeftWing
@@ -150,7 +151,7 @@ impl DumpAction {
             return icing_actions.unwrap();
         }
 
-        let rnd = Game::get_random_in_range(1, 100, 9);
+        let rnd = game.get_random_in_range(1, 100);
 
         return if PROBABILITY_DUMP_OUT_TO_DEFENDER >= rnd {
             self.dump_to_attack_zone(game, DumpOut)
@@ -160,7 +161,7 @@ impl DumpAction {
     }
 
     fn is_pass_catch(&self, game: &mut Game) -> Option<Vec<ActionData>> {
-        let rnd = Game::get_random_in_range(1, 100, 7);
+        let rnd = game.get_random_in_range(1, 100);
 
         if PROBABILITY_PASS_CATCH >= rnd {
             let user_player_id = game.get_player_id_with_puck();
@@ -169,7 +170,7 @@ impl DumpAction {
             let user_with_puck = game.get_user_info(user_player_id.0);
             let active_five = user_with_puck.team.get_active_five();
             let position_to_dump = self.get_random_pos_to_dump(
-                player_position_with_puck, active_five);
+                game, player_position_with_puck, active_five);
             let player_to_dump = game.get_field_player_by_pos(
                 user_player_id.0, position_to_dump);
             let player_with_puck = user_with_puck.team.get_field_player(&user_player_id.1);
@@ -216,7 +217,7 @@ impl DumpAction {
     }
 
     fn is_icing_in_defender_zone(&self, game: &mut Game) -> Option<Vec<ActionData>> {
-        let rnd = Game::get_random_in_range(1, 100, 8);
+        let rnd = game.get_random_in_range(1, 100);
         let player_with_puck_id = game.get_player_id_with_puck();
 
         let user = game.get_user_info(player_with_puck_id.0);
@@ -290,7 +291,7 @@ impl DumpAction {
             }
         }
 
-        let rnd = Game::get_random_in_range(1, positions.len(), 10);
+        let rnd = game.get_random_in_range(1, positions.len());
 
         return positions[rnd];
     }
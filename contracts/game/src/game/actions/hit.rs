@@ -0,0 +1,205 @@
+use near_sdk::log;
+use crate::{FieldPlayer, Game, TokenId};
+use crate::game::actions::action::{ActionData, ActionTypes};
+use crate::game::actions::action::ActionData::{DelayedPenaltySignal, Hit, PokeCheck};
+use crate::game::actions::random_actions::RandomAction;
+use crate::game::actions::utils::{get_relative_field_player_stat, has_won};
+use crate::user_info::UserId;
+
+const PROBABILITY_HIT: usize = 6;
+const PROBABILITY_POKE_CHECK: usize = 6;
+
+// For a player with equal aggressiveness and discipline the chance of a penalty is half of it
+const MAX_PROBABILITY_HIT_PENALTY: f32 = 20.0;
+
+const HIT_STRENGTH_LOSS: u8 = 2;
+
+/// Body check by the opponent of the player with the puck. Only successful checks are generated
+pub struct HitAction;
+impl RandomAction for HitAction {
+    fn check_probability(&self, game: &Game) -> bool {
+        let rnd = game.get_random_in_range(1, 100);
+        if PROBABILITY_HIT >= rnd {
+            return is_check_won(game, |opponent| {
+                (opponent.stats.body_checking as f32 + opponent.stats.strength as f32) / 2.0
+            });
+        }
+
+        false
+    }
+
+    fn do_action(&self, game: &mut Game) -> Vec<ActionData> {
+        log!("Hit");
+        let (user_id, player_id) = game.get_player_id_with_puck();
+        let player_with_puck = game.get_player_with_puck();
+        let opponent = game.get_opponent_field_player().1;
+
+        let opponent_user_id = opponent.get_user_id();
+        let opponent_player_id = opponent.get_player_id();
+        let opponent_user = game.get_user_info(opponent_user_id);
+        let opponent_position = opponent_user.team.get_field_player_pos(&opponent_player_id);
+
+        let mut actions = vec![Hit {
+            action_type: ActionTypes::Hit,
+            account_id: opponent_user.account_id.clone(),
+            player_number: opponent.number,
+            player_position: *opponent_position,
+            opponent_number: player_with_puck.number,
+        }];
+
+        let is_penalty = is_hit_penalty(game, opponent);
+
+        let player_with_puck = game.get_user_info_mut(&user_id).team.get_field_player_mut(&player_id);
+        player_with_puck.stats.strength = player_with_puck.stats.strength.saturating_sub(HIT_STRENGTH_LOSS);
+        player_with_puck.stats.morale = player_with_puck.stats.morale.saturating_sub(1);
+
+        let opponent = game.get_user_info_mut(&opponent_user_id).team.get_field_player_mut(&opponent_player_id);
+        opponent.stats.morale = opponent.stats.morale.saturating_add(1);
+
+        game.player_with_puck = Some((opponent_user_id, opponent_player_id.clone()));
+        game.get_skater_box_score_mut(opponent_user_id, &opponent_player_id).takeaways += 1;
+
+        if is_penalty {
+            actions.push(move_player_to_small_penalties(game, opponent_user_id, opponent_player_id));
+        }
+
+        actions
+    }
+}
+
+/// Stick check by the opponent of the player with the puck. Only successful checks are generated
+pub struct PokeCheckAction;
+impl RandomAction for PokeCheckAction {
+    fn check_probability(&self, game: &Game) -> bool {
+        let rnd = game.get_random_in_range(1, 100);
+        if PROBABILITY_POKE_CHECK >= rnd {
+            return is_check_won(game, |opponent| {
+                (opponent.stats.stick_checking as f32 + opponent.stats.defensive_awareness as f32) / 2.0
+            });
+        }
+
+        false
+    }
+
+    fn do_action(&self, game: &mut Game) -> Vec<ActionData> {
+        log!("PokeCheck");
+        let player_with_puck = game.get_player_with_puck();
+        let opponent = game.get_opponent_field_player().1;
+
+        let opponent_user_id = opponent.get_user_id();
+        let opponent_player_id = opponent.get_player_id();
+        let opponent_user = game.get_user_info(opponent_user_id);
+        let opponent_position = opponent_user.team.get_field_player_pos(&opponent_player_id);
+
+        let actions = vec![PokeCheck {
+            action_type: ActionTypes::PockCheck,
+            account_id: opponent_user.account_id.clone(),
+            opponent_number: player_with_puck.number,
+            player_number: opponent.number,
+            player_position: *opponent_position,
+        }];
+
+        game.player_with_puck = Some((opponent_user_id, opponent_player_id.clone()));
+        game.get_skater_box_score_mut(opponent_user_id, &opponent_player_id).takeaways += 1;
+
+        actions
+    }
+}
+
+/// The check of the opponent against balance and puck control of the player with the puck
+fn is_check_won<F: Fn(&FieldPlayer) -> f32>(game: &Game, get_check_stat: F) -> bool {
+    let player_with_puck = game.get_player_with_puck();
+    let player_stat = get_relative_field_player_stat(
+        player_with_puck,
+        (player_with_puck.stats.balance as f32 + player_with_puck.stats.puck_control as f32) / 2.0);
+
+    let opponent = game.get_opponent_field_player();
+    let opponent_stat = get_relative_field_player_stat(opponent.1, get_check_stat(opponent.1)) * opponent.0;
+
+    has_won(game, opponent_stat, player_stat)
+}
+
+fn is_hit_penalty(game: &Game, hitter: &FieldPlayer) -> bool {
+    if game.is_overtime() || game.get_user_info(hitter.get_user_id()).team.get_number_of_penalty_players() >= 2 {
+        return false;
+    }
+
+    let aggressiveness = hitter.stats.aggressiveness as f32;
+    let discipline = hitter.stats.discipline as f32;
+    let probability = aggressiveness / (aggressiveness + discipline) * MAX_PROBABILITY_HIT_PENALTY;
+
+    probability >= game.get_random_in_range(1, 100) as f32
+}
+
+fn move_player_to_small_penalties(game: &mut Game, user_id: UserId, player_id: TokenId) -> ActionData {
+    let penalty_user = game.get_user_info_mut(&user_id);
+    penalty_user.team.players_to_small_penalty.push(player_id);
+
+    DelayedPenaltySignal {
+        action_type: ActionTypes::DelayedPenaltySignal,
+        type_of_penalty: ActionTypes::SmallPenalty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_game, set_context};
+    use crate::user_info::{USER_ID1, USER_ID2};
+
+    fn get_game_with_puck() -> Game {
+        let mut game = get_game(1);
+        game.player_with_puck = Some((USER_ID1, "a-0".to_string()));
+        game
+    }
+
+    #[test]
+    fn hit_takes_the_puck_and_strength() {
+        set_context(accounts(0));
+        let mut game = get_game_with_puck();
+        let stats = game.get_player_with_puck().stats.clone();
+
+        let actions = HitAction.do_action(&mut game);
+
+        assert!(matches!(&actions[0], Hit { account_id, .. } if *account_id == accounts(2)));
+        let (user_id, player_id) = game.get_player_id_with_puck();
+        assert_eq!(user_id, USER_ID2);
+        assert_eq!(game.box_score.team2.skaters[&player_id].takeaways, 1);
+
+        let hit_player = game.user1.team.get_field_player(&"a-0".to_string());
+        assert_eq!(hit_player.stats.strength, stats.strength - HIT_STRENGTH_LOSS);
+        assert_eq!(hit_player.stats.morale, stats.morale - 1);
+    }
+
+    #[test]
+    fn poke_check_takes_only_the_puck() {
+        set_context(accounts(0));
+        let mut game = get_game_with_puck();
+        let stats = game.get_player_with_puck().stats.clone();
+
+        let actions = PokeCheckAction.do_action(&mut game);
+
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(&actions[0], PokeCheck { account_id, .. } if *account_id == accounts(2)));
+        let (user_id, player_id) = game.get_player_id_with_puck();
+        assert_eq!(user_id, USER_ID2);
+        assert_eq!(game.box_score.team2.skaters[&player_id].takeaways, 1);
+        assert_eq!(game.user1.team.get_field_player(&"a-0".to_string()).stats.strength, stats.strength);
+    }
+
+    #[test]
+    fn no_hit_penalty_in_overtime_or_with_two_players_in_the_box() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+        game.user2.team.get_field_player_mut(&"b-0".to_string()).stats.discipline = 0;
+
+        game.turns = game.rules.third_period;
+        let hitter = game.user2.team.get_field_player(&"b-0".to_string()).clone();
+        assert!((0..50).all(|_| !is_hit_penalty(&game, &hitter)));
+
+        game.turns = 0;
+        game.user2.team.players_to_small_penalty = vec!["b-1".to_string(), "b-2".to_string()];
+        assert!((0..50).all(|_| !is_hit_penalty(&game, &hitter)));
+    }
+}
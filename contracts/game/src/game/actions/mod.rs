@@ -1,8 +1,10 @@
 pub mod action;
 mod pass;
 mod shot;
+pub mod shot_type;
 mod dangle;
 mod move_action;
 mod dump;
+mod hit;
 pub mod utils;
 pub mod random_actions;
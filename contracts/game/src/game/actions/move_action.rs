@@ -34,7 +34,7 @@ impl DoAction for MoveAction {
             player_position: position_player_with_puck.clone()
         }];
 
-        let rnd_offside = Game::get_random_in_range(1, 100, 21);
+        let rnd_offside = game.get_random_in_range(1, 100);
         if rnd_offside <= 15 {
             actions.push(Offside {
                 action_type: ActionTypes::Move,
@@ -48,7 +48,7 @@ impl DoAction for MoveAction {
             return actions;
         }
 
-        if has_won(player_stat, opponent_stat) {
+        if has_won(game, player_stat, opponent_stat) {
             game.zone_number = new_zone_number;
         } else {
             let opponent_user = game.get_opponent_info(user.user_id);
@@ -19,7 +19,7 @@ impl DoAction for PassAction {
 
         let user = game.get_user_info(player_with_puck_id.0);
         let number_of_player_in_five = user.team.get_active_five_number_of_player();
-        let pass_to = get_another_random_position(&player_with_puck_pos, number_of_player_in_five);
+        let pass_to = get_another_random_position(game, &player_with_puck_pos, number_of_player_in_five);
         let is_diagonal_pass = is_diagonal_pass(vec![player_with_puck_pos.clone(), pass_to]);
 
         if is_diagonal_pass {
@@ -30,7 +30,7 @@ impl DoAction for PassAction {
         let pass_to_player_id = game.get_field_player_id_by_pos(&pass_to, player_with_puck.get_user_id());
         let pass_to_player = user.team.get_field_player(&pass_to_player_id);
 
-        return if has_won(player_with_puck_stat, opponent_stat) {
+        return if has_won(game, player_with_puck_stat, opponent_stat) {
             let action = vec![Pass {
                 action_type: ActionTypes::Pass,
                 account_id: (user.account_id.clone()),
@@ -42,6 +42,7 @@ impl DoAction for PassAction {
             }];
 
             game.player_with_puck = Option::from((player_with_puck.get_user_id(), pass_to_player_id.clone()));
+            game.last_pass_player_id = Some(player_with_puck_id.1);
 
             action
         } else {
@@ -76,12 +77,13 @@ fn is_diagonal_pass(positions: Vec<PlayerPosition>) -> bool {
 }
 
 fn get_another_random_position(
+    game: &Game,
     player_pos: &PlayerPosition,
     number_of_players_in_five: usize
 ) -> PlayerPosition {
     let player_positions = get_other_positions(player_pos, number_of_players_in_five);
 
-    let random_pos = Game::get_random_in_range(0, player_positions.len(), 5);
+    let random_pos = game.get_random_in_range(0, player_positions.len());
 
     player_positions[random_pos]
 }
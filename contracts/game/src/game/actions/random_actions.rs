@@ -29,8 +29,8 @@ pub trait RandomAction {
 
 pub struct Giveaway;
 impl RandomAction for Giveaway {
-    fn check_probability(&self, _: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 11);
+    fn check_probability(&self, game: &Game) -> bool {
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_GIVEAWAY >= rnd {
             return true;
         }
@@ -40,7 +40,7 @@ impl RandomAction for Giveaway {
 
     fn do_action(&self, game: &mut Game) -> Vec<ActionData> {
         log!("Giveaway");
-        let rnd = Game::get_random_in_range(1, 100, 12);
+        let rnd = game.get_random_in_range(1, 100);
 
         if PROBABILITY_BATTLE >= rnd {
             battle(game)
@@ -53,6 +53,8 @@ impl RandomAction for Giveaway {
             let opponent_user = game.get_user_info(opponent_player.1.get_user_id());
             let opponent_player_position = opponent_user.team.get_field_player_pos(&opponent_player.1.get_player_id());
 
+            let player_with_puck_id = player_with_puck.get_player_id();
+
             let action = vec![ActionData::Giveaway {
                 action_type: ActionTypes::Giveaway,
                 account_id1: user.account_id.clone(),
@@ -63,7 +65,9 @@ impl RandomAction for Giveaway {
                 player_position2: opponent_player_position.clone()
             }];
 
+            let user_id = user.user_id;
             game.player_with_puck = Option::from((opponent_player.1.get_user_id(), opponent_player.1.get_player_id()));
+            game.get_skater_box_score_mut(user_id, &player_with_puck_id).giveaways += 1;
 
             action
         }
@@ -107,7 +111,7 @@ fn battle(game: &mut Game) -> Vec<ActionData> {
         player_position2: opponent_player_position.clone(),
     }];
 
-    if has_won(compared_stat2, compared_stat1) {
+    if has_won(game, compared_stat2, compared_stat1) {
         actions.push(ActionData::BattleWon {
             action_type: ActionTypes::BattleWon,
             account_id: opponent_user.account_id.clone(),
@@ -131,8 +135,8 @@ fn battle(game: &mut Game) -> Vec<ActionData> {
 
 pub struct Takeaway;
 impl RandomAction for Takeaway {
-    fn check_probability(&self, _: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 13);
+    fn check_probability(&self, game: &Game) -> bool {
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_TAKEAWAY >= rnd {
             return true;
         }
@@ -142,7 +146,7 @@ impl RandomAction for Takeaway {
 
     fn do_action(&self, game: &mut Game) -> Vec<ActionData> {
         log!("Takeaway");
-        let rnd = Game::get_random_in_range(1, 100, 14);
+        let rnd = game.get_random_in_range(1, 100);
 
         return if PROBABILITY_BATTLE >= rnd {
             battle(game)
@@ -155,6 +159,9 @@ impl RandomAction for Takeaway {
             let opponent_user = game.get_user_info(opponent_player.1.get_user_id());
             let opponent_player_position = opponent_user.team.get_field_player_pos(&opponent_player.1.get_player_id());
 
+            let opponent_user_id = opponent_player.1.get_user_id();
+            let opponent_player_id = opponent_player.1.get_player_id();
+
             let action = vec![ActionData::Takeaway {
                 action_type: ActionTypes::Takeaway,
                 account_id1: user.account_id.clone(),
@@ -165,7 +172,8 @@ impl RandomAction for Takeaway {
                 player_position2: opponent_player_position.clone()
             }];
 
-            game.player_with_puck = Option::from((opponent_player.1.get_user_id(), opponent_player.1.get_player_id()));
+            game.get_skater_box_score_mut(opponent_user_id, &opponent_player_id).takeaways += 1;
+            game.player_with_puck = Option::from((opponent_user_id, opponent_player_id));
 
             action
         }
@@ -175,7 +183,7 @@ impl RandomAction for Takeaway {
 pub struct PuckOut;
 impl RandomAction for PuckOut {
     fn check_probability(&self, game: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 15);
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_PUCK_OUT >= rnd as f32 {
             return true;
         }
@@ -192,7 +200,7 @@ impl RandomAction for PuckOut {
 pub struct BigPenalty;
 impl RandomAction for BigPenalty {
     fn check_probability(&self, game: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 16);
+        let rnd = game.get_random_in_range(1, 100);
         log!("rnd: {}", rnd);
         if PROBABILITY_BIG_PENALTY >= rnd {
             return true;
@@ -209,7 +217,7 @@ impl RandomAction for BigPenalty {
         let player_stat1 = player_with_puck.stats.discipline as f32;
         let player_stat2 = opponent_player.1.stats.discipline as f32;
 
-        if has_won(player_stat1, player_stat2) {
+        if has_won(game, player_stat1, player_stat2) {
             // The rules were violated by the opponent of the player with the puck
             match game.last_action {
                 Move {..} | Dangle {..} => {
@@ -270,8 +278,8 @@ fn move_player_to_big_penalties(game: &mut Game, player_id: TokenId, user_id: Us
 
 pub struct SmallPenalty;
 impl RandomAction for SmallPenalty {
-    fn check_probability(&self, _: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 17);
+    fn check_probability(&self, game: &Game) -> bool {
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_SMALL_PENALTY >= rnd {
             return true;
         }
@@ -287,7 +295,7 @@ impl RandomAction for SmallPenalty {
         let player_stat1 = player_with_puck.stats.discipline as f32;
         let player_stat2 = opponent_player.1.stats.discipline as f32;
 
-        if has_won(player_stat1, player_stat2) {
+        if has_won(game, player_stat1, player_stat2) {
             // The rules were violated by the opponent of the player with the puck
             match game.last_action {
                 Move {..} | Dangle {..} => {
@@ -330,7 +338,7 @@ fn move_player_to_small_penalties(game: &mut Game, player_id: TokenId, user_id:
 pub struct Fight;
 impl RandomAction for Fight {
     fn check_probability(&self, game: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 11);
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_FIGHT >= rnd as f32 {
             return true;
         }
@@ -367,7 +375,7 @@ impl RandomAction for Fight {
             }
         ];
 
-        if has_won(compared_stat2, compared_stat1) {
+        if has_won(game, compared_stat2, compared_stat1) {
             actions.push(ActionData::FightWon {
                 action_type: ActionTypes::StartGame,
                 account_id: opponent_info.account_id.clone(),
@@ -391,12 +399,12 @@ impl RandomAction for Fight {
             self.reduce_morale_opponent_team(game, &user_id_with_puck);
         }
 
-        actions.push(get_penalty_by_fight_action(game.do_penalty(BIG_PENALTY,
+        actions.push(get_penalty_by_fight_action(game.do_penalty(game.rules.big_penalty,
                         &player1_id,
                         &user2_id,
                         &user1_id)));
 
-        actions.push(game.do_penalty(BIG_PENALTY,
+        actions.push(game.do_penalty(game.rules.big_penalty,
                         &player2_id,
                         &user1_id,
                         &user2_id));
@@ -448,7 +456,7 @@ impl Fight {
 pub struct NetOff;
 impl RandomAction for NetOff {
     fn check_probability(&self, game: &Game) -> bool {
-        let rnd = Game::get_random_in_range(1, 100, 18);
+        let rnd = game.get_random_in_range(1, 100);
         if PROBABILITY_NET_OFF >= rnd as f32 && (game.zone_number != 2) {
             return true;
         }
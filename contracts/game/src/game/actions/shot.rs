@@ -2,6 +2,7 @@ use near_sdk::log;
 use crate::game::actions::action::ActionData::{Goal, Pass, Rebound, Save, Shot, ShotBlocked, ShotMissed};
 use crate::{Game, PlayerPosition};
 use crate::game::actions::action::{ActionData, ActionTypes, DoAction};
+use crate::game::actions::shot_type::{ShotType, ShotZone};
 use crate::game::actions::utils::{get_relative_field_player_stat, has_won};
 use crate::PlayerPosition::{Center, LeftDefender, LeftWing, RightDefender, RightWing};
 use crate::team::players::goalie::Goalie;
@@ -14,10 +15,13 @@ const PROBABILITY_SHOT_MISSED: usize = 20;
 pub struct ShotAction;
 impl DoAction for ShotAction {
     fn do_action(&self, game: &mut Game) -> Vec<ActionData> {
+        let (shooter_user_id, shooter_id) = game.get_player_id_with_puck();
         let opponent_field_player_stat = self.get_opponent_field_player_stats(game);
         let player_with_puck = game.get_player_with_puck();
+        let shot_type = ShotType::choose(game, &player_with_puck.stats, self.has_pass_before_shot(game));
+        let shot_zone = ShotZone::choose(game);
         let player_stat = get_relative_field_player_stat(
-            player_with_puck, player_with_puck.stats.get_shooting());
+            player_with_puck, shot_type.get_stat(&player_with_puck.stats));
 
         let user = game.get_user_info(player_with_puck.get_user_id());
         let player_position = user.team.get_field_player_pos(
@@ -26,10 +30,12 @@ impl DoAction for ShotAction {
             action_type: ActionTypes::Shot,
             account_id: (user.account_id.clone()),
             player_number: player_with_puck.number,
-            player_position: player_position.clone()
+            player_position: player_position.clone(),
+            shot_type,
+            shot_zone,
         }];
 
-        if !has_won(player_stat, opponent_field_player_stat) {
+        if !has_won(game, player_stat, opponent_field_player_stat) {
             let opponent_player = game.get_opponent_field_player();
             let opponent_user = game.get_opponent_info(user.user_id);
             let opponent_user_id = opponent_player.1.get_user_id();
@@ -44,15 +50,18 @@ impl DoAction for ShotAction {
                 player_position: opponent_position.clone()
             });
 
+            game.get_skater_box_score_mut(opponent_user_id, &opponent_player_id).blocked_shots += 1;
             game.player_with_puck = Option::from((opponent_user_id, opponent_player_id));
         } else {
-            if PROBABILITY_SHOT_MISSED >= Game::get_random_in_range(1, 100, 1) {
+            if PROBABILITY_SHOT_MISSED >= game.get_random_in_range(1, 100) {
                 actions.push(self.do_shot_missed(game));
             } else {
-                actions.append(&mut self.fight_against_goalie(game, player_stat));
+                actions.append(&mut self.fight_against_goalie(game, player_stat, shot_type, shot_zone));
             }
         }
 
+        game.record_shot(shooter_user_id, &shooter_id);
+
         actions
     }
 }
@@ -68,7 +77,7 @@ impl ShotAction {
     }
 
     fn do_shot_missed(&self, game: &mut Game) -> ActionData {
-        let random_user_id = Game::get_random_in_range(1, 2, 19);
+        let random_user_id = game.get_random_in_range(1, 2);
         let user_with_puck_id = game.get_user_id_player_with_puck();
 
         let positions = if random_user_id == user_with_puck_id {
@@ -77,7 +86,7 @@ impl ShotAction {
             vec![LeftDefender, RightDefender]
         };
 
-        let rnd = Game::get_random_in_range(1, 2, 21);
+        let rnd = game.get_random_in_range(1, 2);
 
         let random_position = positions[rnd];
         let player_id = game.get_field_player_id_by_pos(&random_position, random_user_id);
@@ -102,10 +111,16 @@ impl ShotAction {
         action
     }
 
-    fn fight_against_goalie(&self, game: &mut Game, field_player_stat: f32) -> Vec<ActionData> {
+    fn fight_against_goalie(
+        &self,
+        game: &mut Game,
+        field_player_stat: f32,
+        shot_type: ShotType,
+        shot_zone: ShotZone
+    ) -> Vec<ActionData> {
         let user_id_player_with_puck = game.get_user_id_player_with_puck();
         return if self.is_goalie_out(game, &user_id_player_with_puck) {
-            self.score_goal(game, &user_id_player_with_puck)
+            self.score_goal(game, &user_id_player_with_puck, shot_type, shot_zone)
         } else {
             let user_id = game.player_with_puck.as_ref().unwrap().0;
             let user_opponent = game.get_opponent_info(user_id);
@@ -114,21 +129,27 @@ impl ShotAction {
 
             let pass_before_shot = self.has_pass_before_shot(game);
             let reflexes = opponent_goalie.get_reflexes_rel_pass(pass_before_shot);
+            let zone_stat = shot_zone.get_goalie_stat(&opponent_goalie.stats);
             let goalie_stat = self.get_relative_goalie_stat(
-                opponent_goalie, reflexes
+                opponent_goalie, (reflexes + zone_stat) / 2.0
             );
 
-            if has_won(field_player_stat, goalie_stat) {
-                self.score_goal(game, &user_id_player_with_puck)
+            if has_won(game, field_player_stat, goalie_stat) {
+                self.score_goal(game, &user_id_player_with_puck, shot_type, shot_zone)
             } else {
-                if PROBABILITY_SAVE >= Game::get_random_in_range(1, 100, 2) {
+                let goalie_number = opponent_goalie.number;
+                let save_account_id = user_opponent.account_id.clone();
+                game.record_save(user_id);
+
+                if PROBABILITY_SAVE >= game.get_random_in_range(1, 100) {
                     vec![Save {
                         action_type: ActionTypes::Save,
-                        account_id: user_opponent.account_id.clone(),
-                        goalie_number: opponent_goalie.number,
+                        account_id: save_account_id,
+                        goalie_number,
+                        shot_type,
+                        shot_zone,
                     }]
                 } else {
-                    let goalie_number = opponent_goalie.number;
                     vec![self.do_rebound(game, goalie_number)]
                 }
             }
@@ -143,7 +164,13 @@ impl ShotAction {
         }
     }
 
-    fn score_goal(&self, game: &mut Game, user_id: &usize) -> Vec<ActionData> {
+    fn score_goal(
+        &self,
+        game: &mut Game,
+        user_id: &usize,
+        shot_type: ShotType,
+        shot_zone: ShotZone
+    ) -> Vec<ActionData> {
         self.change_morale_after_goal(game);
         game.get_user_info_mut(user_id).team.score += 1;
 
@@ -161,11 +188,11 @@ impl ShotAction {
         let user = game.get_user_info(user_id.clone());
         let player_with_puck = game.get_player_with_puck();
 
-        let (pass_player_name, pass_player_num) = match game.last_action.clone() {
+        let (pass_player_name, pass_player_num, pass_player_id) = match game.last_action.clone() {
             Pass { from_player_name, from_player_number, .. } => {
-                (Some(from_player_name), Some(from_player_number))
+                (Some(from_player_name), Some(from_player_number), game.last_pass_player_id.clone())
             },
-            _ => (None, None)
+            _ => (None, None, None)
         };
 
         actions.push(Goal {
@@ -175,9 +202,14 @@ impl ShotAction {
             player_img: player_with_puck.img.clone().expect("Player img not found"),
             player_number1: player_with_puck.number,
             player_name2: pass_player_name,
-            player_number2: pass_player_num
+            player_number2: pass_player_num,
+            shot_type,
+            shot_zone,
         });
 
+        let scorer_id = player_with_puck.get_player_id();
+        game.record_goal(*user_id, &scorer_id, pass_player_id, false);
+
         actions
     }
 
@@ -236,7 +268,7 @@ impl ShotAction {
     }
 
     fn do_rebound(&self, game: &mut Game, goalie_number: u8) -> ActionData {
-        let random_user_id = Game::get_random_in_range(1, 2, 19);
+        let random_user_id = game.get_random_in_range(1, 2);
         let user_with_puck_id = game.get_user_id_player_with_puck();
 
         let positions = if random_user_id == user_with_puck_id {
@@ -245,7 +277,7 @@ impl ShotAction {
             vec![LeftDefender, RightDefender, Center]
         };
 
-        let rnd = Game::get_random_in_range(1, 3, 20);
+        let rnd = game.get_random_in_range(1, 3);
 
         let random_position = positions[rnd];
         let player_id = game.get_field_player_id_by_pos(&random_position, random_user_id.clone());
@@ -267,4 +299,38 @@ impl ShotAction {
 
         action
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::user_info::USER_ID1;
+    use crate::test_utils::{get_game, set_context};
+
+    #[test]
+    fn shot_at_an_empty_net_keeps_type_and_zone() {
+        for game_id in 1..20 {
+            set_context(accounts(0));
+            let mut game = get_game(game_id);
+            game.player_with_puck = Some((USER_ID1, "a-0".to_string()));
+            game.user1.is_goalie_out = true;
+
+            let actions = ShotAction.do_action(&mut game);
+
+            let (shot_type, shot_zone) = match &actions[0] {
+                Shot { shot_type, shot_zone, .. } => (*shot_type, *shot_zone),
+                _ => panic!("Shot expected"),
+            };
+            assert!(matches!(shot_type, ShotType::Slap | ShotType::Wrist | ShotType::Backhand));
+            assert_eq!(game.get_skater_box_score_mut(USER_ID1, &"a-0".to_string()).shots, 1);
+
+            match &actions[1] {
+                Goal { shot_type: goal_type, shot_zone: goal_zone, .. } => {
+                    assert_eq!((*goal_type, *goal_zone), (shot_type, shot_zone));
+                }
+                ShotBlocked { .. } | ShotMissed { .. } => {}
+                _ => panic!("Unexpected action after a shot at an empty net"),
+            }
+        }
+    }
+}
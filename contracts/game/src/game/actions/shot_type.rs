@@ -0,0 +1,160 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::Game;
+use crate::game::actions::utils::has_won;
+use crate::team::players::field_player::FieldPlayerStats;
+use crate::team::players::goalie::GoalieStats;
+
+const PROBABILITY_DEFLECTION: usize = 20;
+const PROBABILITY_BACKHAND: usize = 15;
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Debug, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ShotType {
+    Slap,
+    Wrist,
+    Backhand,
+    OneTimer,
+    Deflection,
+}
+
+impl ShotType {
+    /// One-timers and deflections are only possible right after a pass
+    pub fn choose(game: &Game, stats: &FieldPlayerStats, pass_before_shot: bool) -> ShotType {
+        if pass_before_shot {
+            return if PROBABILITY_DEFLECTION >= game.get_random_in_range(1, 100) {
+                ShotType::Deflection
+            } else {
+                ShotType::OneTimer
+            };
+        }
+
+        ShotType::choose_without_pass(game, stats)
+    }
+
+    pub fn choose_without_pass(game: &Game, stats: &FieldPlayerStats) -> ShotType {
+        if PROBABILITY_BACKHAND >= game.get_random_in_range(1, 100) {
+            return ShotType::Backhand;
+        }
+
+        // The stronger of the two shots is more likely
+        if has_won(game, ShotType::Slap.get_stat(stats), ShotType::Wrist.get_stat(stats)) {
+            ShotType::Slap
+        } else {
+            ShotType::Wrist
+        }
+    }
+
+    pub fn get_stat(&self, stats: &FieldPlayerStats) -> f32 {
+        match self {
+            ShotType::Slap => (stats.slap_shot_accuracy as f32 + stats.slap_shot_power as f32) / 2.0,
+            ShotType::Wrist => (stats.wrist_shot_accuracy as f32 + stats.wrist_shot_power as f32) / 2.0,
+            ShotType::Backhand => (stats.wrist_shot_accuracy as f32
+                + stats.deking as f32
+                + stats.puck_control as f32) / 3.0,
+            ShotType::OneTimer => (stats.slap_shot_accuracy as f32
+                + stats.slap_shot_power as f32
+                + stats.hand_eye as f32) / 3.0,
+            ShotType::Deflection => (stats.hand_eye as f32 + stats.offensive as f32) / 2.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Debug, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ShotZone {
+    FiveHole,
+    GloveSideHigh,
+    GloveSideLow,
+    StickSideHigh,
+    StickSideLow,
+}
+
+impl ShotZone {
+    pub fn choose(game: &Game) -> ShotZone {
+        match game.get_random_in_range(1, 6) {
+            1 => ShotZone::FiveHole,
+            2 => ShotZone::GloveSideHigh,
+            3 => ShotZone::GloveSideLow,
+            4 => ShotZone::StickSideHigh,
+            _ => ShotZone::StickSideLow,
+        }
+    }
+
+    pub fn get_goalie_stat(&self, stats: &GoalieStats) -> f32 {
+        let stat = match self {
+            ShotZone::FiveHole => stats.five_hole,
+            ShotZone::GloveSideHigh => stats.glove_side_high,
+            ShotZone::GloveSideLow => stats.glove_side_low,
+            ShotZone::StickSideHigh => stats.stick_side_high,
+            ShotZone::StickSideLow => stats.stick_side_low,
+        };
+
+        stat as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::team::numbers::GoalieNumber;
+    use crate::test_utils::{get_game, set_context};
+
+    #[test]
+    fn one_timers_and_deflections_only_after_a_pass() {
+        set_context(accounts(0));
+        let game = get_game(1);
+        let stats = game.user1.team.get_field_player(&"a-0".to_string()).stats.clone();
+
+        for _ in 0..100 {
+            let after_pass = ShotType::choose(&game, &stats, true);
+            assert!(matches!(after_pass, ShotType::OneTimer | ShotType::Deflection));
+
+            let without_pass = ShotType::choose(&game, &stats, false);
+            assert!(matches!(without_pass, ShotType::Slap | ShotType::Wrist | ShotType::Backhand));
+        }
+    }
+
+    #[test]
+    fn shot_stat_depends_on_type() {
+        set_context(accounts(0));
+        let game = get_game(1);
+        let mut stats = game.user1.team.get_field_player(&"a-0".to_string()).stats.clone();
+        stats.slap_shot_accuracy = 90;
+        stats.slap_shot_power = 70;
+        stats.hand_eye = 20;
+
+        assert_eq!(ShotType::Slap.get_stat(&stats), 80.0);
+        assert_eq!(ShotType::Wrist.get_stat(&stats), 50.0);
+        assert_eq!(ShotType::OneTimer.get_stat(&stats), 60.0);
+        assert_eq!(ShotType::Deflection.get_stat(&stats), 35.0);
+    }
+
+    #[test]
+    fn every_zone_is_aimed_at() {
+        set_context(accounts(0));
+        let game = get_game(1);
+
+        let zones: Vec<ShotZone> = (0..200).map(|_| ShotZone::choose(&game)).collect();
+        for zone in [ShotZone::FiveHole, ShotZone::GloveSideHigh, ShotZone::GloveSideLow,
+            ShotZone::StickSideHigh, ShotZone::StickSideLow] {
+            assert!(zones.contains(&zone));
+        }
+    }
+
+    #[test]
+    fn goalie_stat_depends_on_zone() {
+        set_context(accounts(0));
+        let game = get_game(1);
+        let mut stats = game.user1.team.goalies[&GoalieNumber::MainGoalkeeper].stats.clone();
+        stats.glove_side_high = 90;
+        stats.stick_side_low = 10;
+
+        assert_eq!(ShotZone::GloveSideHigh.get_goalie_stat(&stats), 90.0);
+        assert_eq!(ShotZone::StickSideLow.get_goalie_stat(&stats), 10.0);
+        assert_eq!(ShotZone::FiveHole.get_goalie_stat(&stats), 50.0);
+    }
+}
@@ -1,10 +1,10 @@
 use crate::{FieldPlayer, Game, UserInfo};
 
 
-pub fn has_won(stat: f32, opponents_stat: f32) -> bool {
+pub fn has_won(game: &Game, stat: f32, opponents_stat: f32) -> bool {
     let sum = stat + opponents_stat;
 
-    let random_number = Game::get_random_in_range(1, sum.round() as usize + 1, 5);
+    let random_number = game.get_random_in_range(1, sum.round() as usize + 1);
 
     return if stat > opponents_stat {
         if random_number as f32 > opponents_stat {
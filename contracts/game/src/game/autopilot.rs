@@ -0,0 +1,271 @@
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::team::five::{IceTimePriority, Tactics};
+use crate::team::numbers::FiveNumber;
+use crate::team::players::player::GoalieSubstitution;
+use crate::user_info::{USER_ID1, USER_ID2};
+
+pub const MAX_AUTOPILOT_RULES: usize = 10;
+
+/// Goals are counted from the side of the manager of the policy
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AutopilotCondition {
+    // Own goals minus goals of the opponent, both bounds included
+    ScoreDifference { min: i8, max: i8 },
+    // 4 for overtime
+    FromPeriod { period: u8 },
+    // The last turns of the third period
+    EndOfThirdPeriod { turns: u8 },
+    // Average strength of the players on the ice
+    LineStrengthBelow { strength: u8 },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AutopilotDecision {
+    TakeTO,
+    CoachSpeech,
+    GoalieOut { goalie_substitution: GoalieSubstitution },
+    GoalieBack,
+    // Every five if the five is not set
+    ChangeTactic { five: Option<FiveNumber>, tactic: Tactics },
+    ChangeIcePriority { five: Option<FiveNumber>, ice_time_priority: IceTimePriority },
+}
+
+/// The decision is made when all the conditions are met
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AutopilotRule {
+    pub(crate) conditions: Vec<AutopilotCondition>,
+    pub(crate) decision: AutopilotDecision,
+}
+
+/// Rules are checked in order at every stoppage
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AutopilotPolicy {
+    pub(crate) rules: Vec<AutopilotRule>,
+}
+
+impl AutopilotPolicy {
+    pub fn assert_valid(&self) {
+        assert!(self.rules.len() <= MAX_AUTOPILOT_RULES, "At most {} rules are allowed", MAX_AUTOPILOT_RULES);
+
+        for rule in &self.rules {
+            for condition in &rule.conditions {
+                if let AutopilotCondition::ScoreDifference { min, max } = condition {
+                    assert!(min <= max, "Wrong score difference");
+                }
+            }
+        }
+    }
+}
+
+impl UserInfo {
+    fn get_five_numbers(&self, five: &Option<FiveNumber>) -> Vec<FiveNumber> {
+        match five {
+            Some(five) => vec![*five],
+            None => self.team.fives.keys().cloned().collect(),
+        }
+    }
+}
+
+impl Game {
+    fn is_autopilot_condition_met(&self, user_id: UserId, condition: &AutopilotCondition) -> bool {
+        match condition {
+            AutopilotCondition::ScoreDifference { min, max } => {
+                let difference = self.get_user_info(user_id).team.score as i16
+                    - self.get_opponent_info(user_id).team.score as i16;
+                *min as i16 <= difference && difference <= *max as i16
+            },
+            AutopilotCondition::FromPeriod { period } => self.get_period() >= *period,
+            AutopilotCondition::EndOfThirdPeriod { turns } => {
+                self.get_period() == 3 && self.turns as u16 + *turns as u16 >= self.rules.third_period as u16
+            },
+            AutopilotCondition::LineStrengthBelow { strength } => {
+                let team = &self.get_user_info(user_id).team;
+                let strengths: Vec<f32> = team.get_active_five().field_players.values()
+                    .filter(|player_id| !player_id.is_empty())
+                    .map(|player_id| team.get_field_player(player_id).stats.get_strength())
+                    .collect();
+
+                !strengths.is_empty() && strengths.iter().sum::<f32>() / (strengths.len() as f32) < *strength as f32
+            },
+        }
+    }
+
+    /// Returns the action to show in the event. Nothing if the decision changes nothing or is silent
+    fn make_autopilot_decision(&mut self, user_id: UserId, decision: &AutopilotDecision) -> Option<ActionData> {
+        let (user, opponent) = if user_id == USER_ID1 {
+            (&mut self.user1, &mut self.user2)
+        } else {
+            (&mut self.user2, &mut self.user1)
+        };
+        let account_id = user.account_id.clone();
+
+        match decision {
+            AutopilotDecision::TakeTO if !user.take_to_called => {
+                user.change_stats_take_to(opponent);
+                Some(ActionData::TakeTO { action_type: ActionTypes::TakeTO, account_id })
+            },
+            AutopilotDecision::CoachSpeech if !user.coach_speech_called => {
+                user.change_stats_coach_speech();
+                Some(ActionData::CoachSpeech { action_type: ActionTypes::CoachSpeech, account_id })
+            },
+            AutopilotDecision::GoalieOut { goalie_substitution } if !user.is_goalie_out => {
+                user.goalie_out(*goalie_substitution);
+                Some(ActionData::GoalieOut { action_type: ActionTypes::GoalieOut, account_id })
+            },
+            AutopilotDecision::GoalieBack if user.is_goalie_out => {
+                user.goalie_back();
+                Some(ActionData::GoalieBack { action_type: ActionTypes::GoalieBack, account_id })
+            },
+            AutopilotDecision::ChangeTactic { five, tactic } => {
+                for number in user.get_five_numbers(five) {
+                    if let Some(five_ids) = user.team.fives.get_mut(&number) {
+                        five_ids.tactic = *tactic;
+                    }
+                    if user.team.active_five.current_number == number {
+                        user.team.active_five.tactic = *tactic;
+                    }
+                }
+                None
+            },
+            AutopilotDecision::ChangeIcePriority { five, ice_time_priority } => {
+                for number in user.get_five_numbers(five) {
+                    if let Some(five_ids) = user.team.fives.get_mut(&number) {
+                        five_ids.ice_time_priority = *ice_time_priority;
+                    }
+                    if user.team.active_five.current_number == number {
+                        user.team.active_five.ice_time_priority = *ice_time_priority;
+                    }
+                }
+                None
+            },
+            _ => None,
+        }
+    }
+
+    /// Makes the decisions of both managers whose rules are met. Called at every stoppage
+    pub(crate) fn run_autopilot(&mut self) -> Vec<ActionData> {
+        let mut actions = vec![];
+
+        for user_id in [USER_ID1, USER_ID2] {
+            let policy = match self.get_user_info(user_id).autopilot.clone() {
+                Some(policy) => policy,
+                None => continue,
+            };
+
+            for rule in &policy.rules {
+                if rule.conditions.iter().all(|condition| self.is_autopilot_condition_met(user_id, condition)) {
+                    if let Some(action) = self.make_autopilot_decision(user_id, &rule.decision) {
+                        actions.push(action);
+                    }
+                }
+            }
+        }
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    fn get_rule(conditions: Vec<AutopilotCondition>) -> AutopilotRule {
+        AutopilotRule { conditions, decision: AutopilotDecision::CoachSpeech }
+    }
+
+    fn get_policy(number_of_rules: usize) -> AutopilotPolicy {
+        AutopilotPolicy { rules: (0..number_of_rules).map(|_| get_rule(vec![])).collect() }
+    }
+
+    #[test]
+    fn empty_policy_is_valid() {
+        get_policy(0).assert_valid();
+        get_policy(1).assert_valid();
+    }
+
+    #[test]
+    fn max_number_of_rules() {
+        get_policy(MAX_AUTOPILOT_RULES).assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "At most 10 rules are allowed")]
+    fn too_many_rules() {
+        get_policy(MAX_AUTOPILOT_RULES + 1).assert_valid();
+    }
+
+    #[test]
+    fn score_difference_bounds() {
+        let policy = AutopilotPolicy {
+            rules: vec![
+                get_rule(vec![AutopilotCondition::ScoreDifference { min: 0, max: 0 }]),
+                get_rule(vec![AutopilotCondition::ScoreDifference { min: i8::MIN, max: i8::MAX }]),
+                get_rule(vec![AutopilotCondition::ScoreDifference { min: -3, max: -1 }, AutopilotCondition::FromPeriod { period: 4 }]),
+            ],
+        };
+        policy.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong score difference")]
+    fn min_above_max() {
+        let policy = AutopilotPolicy {
+            rules: vec![get_rule(vec![AutopilotCondition::ScoreDifference { min: 1, max: 0 }])],
+        };
+        policy.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong score difference")]
+    fn wrong_condition_in_a_later_rule() {
+        let policy = AutopilotPolicy {
+            rules: vec![
+                get_rule(vec![AutopilotCondition::FromPeriod { period: 2 }]),
+                get_rule(vec![AutopilotCondition::EndOfThirdPeriod { turns: 5 }, AutopilotCondition::ScoreDifference { min: i8::MAX, max: i8::MIN }]),
+            ],
+        };
+        policy.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong score difference")]
+    fn invalid_policy_is_not_saved() {
+        set_context(accounts(1));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.set_autopilot_policy(Some(AutopilotPolicy {
+            rules: vec![get_rule(vec![AutopilotCondition::ScoreDifference { min: 2, max: 1 }])],
+        }));
+    }
+
+    #[test]
+    fn autopilot_decides_at_stoppage() {
+        set_context(accounts(1));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.set_autopilot_policy(Some(AutopilotPolicy {
+            rules: vec![AutopilotRule { conditions: vec![], decision: AutopilotDecision::CoachSpeech }],
+        }));
+
+        let mut game = hockey.internal_create_game(accounts(1), accounts(2), TokenBalance {
+            token_id: Some(NEAR.into()),
+            balance: 0,
+        }, Default::default(), (get_team_metadata("a"), get_team_metadata("b")), true);
+        assert!(game.user2.autopilot.is_none());
+
+        // The game starts with a face-off
+        let event = hockey.internal_generate_event(&mut game);
+        assert!(event.actions.iter().any(|action| matches!(action, CoachSpeech { .. })));
+        assert!(game.user1.coach_speech_called);
+        assert!(!game.user2.coach_speech_called);
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::*;
+use crate::team::numbers::GoalieNumber;
+use crate::user_info::UserId;
+
+const BIG_PENALTY_MINUTES: u16 = 5;
+const SMALL_PENALTY_MINUTES: u16 = 2;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SkaterBoxScore {
+    pub(crate) goals: u16,
+    pub(crate) assists: u16,
+    pub(crate) shots: u16,
+    pub(crate) blocked_shots: u16,
+    pub(crate) giveaways: u16,
+    pub(crate) takeaways: u16,
+    pub(crate) faceoffs_won: u16,
+    pub(crate) penalty_minutes: u16,
+    pub(crate) plus_minus: i16,
+    // Turns on the ice
+    pub(crate) time_on_ice: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GoalieBoxScore {
+    pub(crate) saves: u16,
+    pub(crate) shots_against: u16,
+    pub(crate) goals_against: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TeamBoxScore {
+    pub(crate) account_id: AccountId,
+    pub(crate) skaters: BTreeMap<TokenId, SkaterBoxScore>,
+    pub(crate) goalies: BTreeMap<GoalieNumber, GoalieBoxScore>,
+}
+
+impl TeamBoxScore {
+    pub fn new(account_id: AccountId) -> Self {
+        TeamBoxScore {
+            account_id,
+            skaters: BTreeMap::new(),
+            goalies: BTreeMap::new(),
+        }
+    }
+}
+
+/// Teams are stored by account, since user ids are swapped every period
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BoxScore {
+    pub(crate) team1: TeamBoxScore,
+    pub(crate) team2: TeamBoxScore,
+}
+
+impl BoxScore {
+    pub fn new(account_id1: AccountId, account_id2: AccountId) -> Self {
+        BoxScore {
+            team1: TeamBoxScore::new(account_id1),
+            team2: TeamBoxScore::new(account_id2),
+        }
+    }
+
+    pub fn get_team_mut(&mut self, account_id: &AccountId) -> &mut TeamBoxScore {
+        if self.team1.account_id == *account_id {
+            &mut self.team1
+        } else {
+            &mut self.team2
+        }
+    }
+}
+
+impl Game {
+    pub fn get_skater_box_score_mut(&mut self, user_id: UserId, player_id: &TokenId) -> &mut SkaterBoxScore {
+        let account_id = self.get_user_info(user_id).account_id.clone();
+        self.box_score.get_team_mut(&account_id).skaters
+            .entry(player_id.clone())
+            .or_default()
+    }
+
+    /// None if the goalie is pulled
+    pub fn get_active_goalie_box_score_mut(&mut self, user_id: UserId) -> Option<&mut GoalieBoxScore> {
+        let user = self.get_user_info(user_id);
+        if user.is_goalie_out {
+            return None;
+        }
+
+        let account_id = user.account_id.clone();
+        let goalie_number = user.team.active_goalie.clone();
+
+        Some(self.box_score.get_team_mut(&account_id).goalies
+            .entry(goalie_number)
+            .or_default())
+    }
+
+    pub fn record_shot(&mut self, user_id: UserId, player_id: &TokenId) {
+        self.get_skater_box_score_mut(user_id, player_id).shots += 1;
+    }
+
+    /// Shot on goal stopped by the goalie of the opponent of `user_id`
+    pub fn record_save(&mut self, user_id: UserId) {
+        let opponent_id = self.get_opponent_info(user_id).user_id;
+        if let Some(goalie) = self.get_active_goalie_box_score_mut(opponent_id) {
+            goalie.shots_against += 1;
+            goalie.saves += 1;
+        }
+    }
+
+    pub fn record_goal(
+        &mut self,
+        user_id: UserId,
+        scorer_id: &TokenId,
+        assistant_id: Option<TokenId>,
+        is_penalty_shot: bool
+    ) {
+        self.get_skater_box_score_mut(user_id, scorer_id).goals += 1;
+        if let Some(assistant_id) = assistant_id {
+            self.get_skater_box_score_mut(user_id, &assistant_id).assists += 1;
+        }
+
+        let opponent_id = self.get_opponent_info(user_id).user_id;
+        if let Some(goalie) = self.get_active_goalie_box_score_mut(opponent_id) {
+            goalie.shots_against += 1;
+            goalie.goals_against += 1;
+        }
+
+        if !is_penalty_shot {
+            self.change_plus_minus(user_id, 1);
+            self.change_plus_minus(opponent_id, -1);
+        }
+    }
+
+    fn get_players_on_ice(&self, user_id: UserId) -> Vec<TokenId> {
+        self.get_user_info(user_id).team.get_active_five()
+            .field_players.values()
+            .filter(|player_id| !player_id.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    fn change_plus_minus(&mut self, user_id: UserId, value: i16) {
+        let players_on_ice = self.get_players_on_ice(user_id);
+
+        for player_id in &players_on_ice {
+            self.get_skater_box_score_mut(user_id, player_id).plus_minus += value;
+        }
+    }
+
+    pub fn record_time_on_ice(&mut self, user_id: UserId) {
+        let players_on_ice = self.get_players_on_ice(user_id);
+
+        for player_id in &players_on_ice {
+            self.get_skater_box_score_mut(user_id, player_id).time_on_ice += 1;
+        }
+    }
+
+    pub fn record_penalty(&mut self, user_id: UserId, player_id: &TokenId, penalty_time: u8) {
+        let minutes = if penalty_time == self.rules.big_penalty {
+            BIG_PENALTY_MINUTES
+        } else {
+            SMALL_PENALTY_MINUTES
+        };
+
+        self.get_skater_box_score_mut(user_id, player_id).penalty_minutes += minutes;
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn get_box_score(&self, game_id: GameId) -> BoxScore {
+        self.internal_get_game(&game_id).box_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_game, set_context};
+    use crate::user_info::{USER_ID1, USER_ID2};
+
+    fn get_skater(game: &Game, account_index: usize, player_id: &str) -> SkaterBoxScore {
+        let team = if account_index == 1 { &game.box_score.team1 } else { &game.box_score.team2 };
+        team.skaters.get(player_id).cloned().unwrap_or_default()
+    }
+
+    fn get_goalie(game: &Game, account_index: usize) -> GoalieBoxScore {
+        let team = if account_index == 1 { &game.box_score.team1 } else { &game.box_score.team2 };
+        team.goalies.get(&GoalieNumber::MainGoalkeeper).cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn goal_counts_for_scorer_assistant_goalie_and_players_on_ice() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+
+        game.record_shot(USER_ID1, &"a-0".to_string());
+        game.record_goal(USER_ID1, &"a-0".to_string(), Some("a-1".to_string()), false);
+
+        let scorer = get_skater(&game, 1, "a-0");
+        assert_eq!((scorer.goals, scorer.assists, scorer.shots, scorer.plus_minus), (1, 0, 1, 1));
+        let assistant = get_skater(&game, 1, "a-1");
+        assert_eq!((assistant.goals, assistant.assists, assistant.plus_minus), (0, 1, 1));
+        assert_eq!(get_skater(&game, 1, "a-4").plus_minus, 1);
+        assert_eq!(get_skater(&game, 2, "b-2").plus_minus, -1);
+
+        let goalie = get_goalie(&game, 2);
+        assert_eq!((goalie.shots_against, goalie.goals_against, goalie.saves), (1, 1, 0));
+    }
+
+    #[test]
+    fn penalty_shot_goal_does_not_change_plus_minus() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+
+        game.record_goal(USER_ID2, &"b-0".to_string(), None, true);
+
+        assert_eq!(get_skater(&game, 2, "b-0").goals, 1);
+        assert_eq!(get_skater(&game, 2, "b-0").plus_minus, 0);
+        assert_eq!(get_skater(&game, 1, "a-0").plus_minus, 0);
+        assert_eq!(get_goalie(&game, 1).goals_against, 1);
+    }
+
+    #[test]
+    fn save_counts_only_with_goalie_in_net() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+
+        game.record_save(USER_ID1);
+        game.user2.is_goalie_out = true;
+        game.record_save(USER_ID1);
+
+        let goalie = get_goalie(&game, 2);
+        assert_eq!((goalie.shots_against, goalie.saves), (1, 1));
+    }
+
+    #[test]
+    fn penalty_minutes_depend_on_penalty_length() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+        let (big_penalty, small_penalty) = (game.rules.big_penalty, game.rules.small_penalty);
+
+        game.record_penalty(USER_ID1, &"a-3".to_string(), big_penalty);
+        game.record_penalty(USER_ID1, &"a-3".to_string(), small_penalty);
+
+        assert_eq!(get_skater(&game, 1, "a-3").penalty_minutes, BIG_PENALTY_MINUTES + SMALL_PENALTY_MINUTES);
+    }
+
+    #[test]
+    fn time_on_ice_counts_only_the_active_five() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+
+        game.record_time_on_ice(USER_ID1);
+        game.record_time_on_ice(USER_ID1);
+
+        assert_eq!(game.box_score.team1.skaters.len(), 5);
+        assert_eq!(get_skater(&game, 1, "a-2").time_on_ice, 2);
+        assert!(game.box_score.team2.skaters.is_empty());
+    }
+
+    #[test]
+    fn stats_follow_the_account_when_sides_are_swapped() {
+        set_context(accounts(0));
+        let mut game = get_game(1);
+
+        std::mem::swap(&mut game.user1, &mut game.user2);
+        game.record_shot(USER_ID1, &"b-0".to_string());
+
+        assert_eq!(get_skater(&game, 2, "b-0").shots, 1);
+        assert!(game.box_score.team1.skaters.is_empty());
+    }
+}
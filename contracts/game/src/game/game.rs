@@ -1,28 +1,34 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use crate::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{AccountId, env, Timestamp};
 use near_sdk::serde::{Deserialize, Serialize};
 use crate::team::players::field_player::{FieldPlayer};
-use crate::game::actions::action::{Action, ActionData, ActionTypes};
+use crate::game::actions::action::{Action, ActionData, ActionDataV1, ActionTypes};
 use crate::game::actions::action::ActionData::*;
 use crate::team::players::player::{PlayerPosition};
 use crate::team::players::player::PlayerPosition::*;
 use crate::{TokenBalance};
 use crate::ActionTypes::{BigPenalty, SmallPenalty};
-use crate::game::actions::random_actions::{BIG_PENALTY, SMALL_PENALTY};
 use crate::game::actions::utils::{get_relative_field_player_stat, has_won};
+use crate::game::actions::shot_type::{ShotType, ShotZone};
+use crate::game::box_score::BoxScore;
+use crate::game::match_rules::MatchRules;
+use crate::game::rng::{ChainRng, GameRng, Rng};
 use crate::PlayerPosition::LeftWing;
 use crate::team::five::{ActiveFive, FiveIds, IceTimePriority};
 use crate::team::numbers::FiveNumber;
 use crate::team::numbers::FiveNumber::{First, PenaltyKill1, PenaltyKill2, PowerPlay1, PowerPlay2};
 use crate::team::players::player::Hand::Left;
 use crate::team::team_metadata::team_metadata_to_team;
-use crate::user_info::{USER_ID1, USER_ID2, UserId};
+use crate::user_info::{USER_ID1, USER_ID2, UserId, UserInfoV1};
 
 pub const FIRST_PERIOD: u8 = 25;
 pub const SECOND_PERIOD: u8 = 50;
 pub const THIRD_PERIOD: u8 = 75;
+pub const OVERTIME_PERIOD: u8 = 90;
+
+pub const SHOOTOUT_ROUNDS: usize = 3;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum GameState {
@@ -44,7 +50,46 @@ pub struct Event {
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ShootoutState {
+    // User id of the team that shoots next
+    pub(crate) next_user_id: UserId,
+
+    pub(crate) goals1: u8,
+    pub(crate) goals2: u8,
+
+    // Players who have already shot, in order
+    pub(crate) shooters1: Vec<TokenId>,
+    pub(crate) shooters2: Vec<TokenId>,
+}
+
+impl ShootoutState {
+    /// Decided as soon as one team cannot catch up in the regular rounds,
+    /// then after every pair of attempts in sudden death
+    pub fn get_winner(&self) -> Option<UserId> {
+        let attempts1 = self.shooters1.len();
+        let attempts2 = self.shooters2.len();
+
+        if attempts1 <= SHOOTOUT_ROUNDS && attempts2 <= SHOOTOUT_ROUNDS {
+            let remaining_attempts1 = (SHOOTOUT_ROUNDS - attempts1) as u8;
+            let remaining_attempts2 = (SHOOTOUT_ROUNDS - attempts2) as u8;
+
+            if self.goals1 + remaining_attempts1 < self.goals2 {
+                return Some(USER_ID2);
+            } else if self.goals2 + remaining_attempts2 < self.goals1 {
+                return Some(USER_ID1);
+            }
+        } else if attempts1 == attempts2 && self.goals1 != self.goals2 {
+            return if self.goals1 > self.goals2 { Some(USER_ID1) } else { Some(USER_ID2) };
+        }
+
+        None
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Game {
     pub(crate) game_id: GameId,
@@ -64,6 +109,87 @@ pub struct Game {
     pub(crate) event_generation_delay: u64,
     pub(crate) max_number_of_generated_events_in_block: u8,
     pub(crate) number_of_generated_events_in_current_block: u8,
+
+    pub(crate) rng: Rng,
+    pub(crate) shootout: Option<ShootoutState>,
+    pub(crate) rules: MatchRules,
+
+    pub(crate) box_score: BoxScore,
+    // The player who made the last completed pass, for assists
+    pub(crate) last_pass_player_id: Option<TokenId>,
+    // Stopped by the owner, stakes are refunded
+    pub(crate) is_aborted: bool,
+    // The player who generated the last event, wins an abandoned game. Users swap sides between periods
+    pub(crate) last_active_account: Option<AccountId>,
+}
+
+/// Layout of `Game` in the first version
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct GameV1 {
+    pub(crate) game_id: GameId,
+    pub(crate) user1: UserInfoV1,
+    pub(crate) user2: UserInfoV1,
+    pub(crate) reward: TokenBalance,
+    pub(crate) winner_index: Option<usize>,
+    pub(crate) player_with_puck: Option<(UserId, TokenId)>,
+    pub(crate) zone_number: u8,
+    pub(crate) turns: u8,
+    pub(crate) last_action: ActionDataV1,
+    pub(crate) last_event_generation_time: Timestamp,
+    pub(crate) event_generation_delay: u64,
+    pub(crate) max_number_of_generated_events_in_block: u8,
+    pub(crate) number_of_generated_events_in_current_block: u8,
+}
+
+impl From<GameV1> for Game {
+    fn from(game: GameV1) -> Self {
+        let box_score = BoxScore::new(game.user1.account_id.clone(), game.user2.account_id.clone());
+        let last_action = match game.last_action.0 {
+            GameFinished { action_type, winner_account_id, reward, .. } => GameFinished {
+                action_type,
+                winner_account_id,
+                reward,
+                box_score: box_score.clone(),
+            },
+            action => action,
+        };
+
+        Game {
+            game_id: game.game_id,
+            user1: game.user1.into(),
+            user2: game.user2.into(),
+            reward: game.reward,
+            winner_index: game.winner_index,
+            player_with_puck: game.player_with_puck,
+            zone_number: game.zone_number,
+            turns: game.turns,
+            last_action,
+            last_event_generation_time: game.last_event_generation_time,
+            event_generation_delay: game.event_generation_delay,
+            max_number_of_generated_events_in_block: game.max_number_of_generated_events_in_block,
+            number_of_generated_events_in_current_block: game.number_of_generated_events_in_current_block,
+            rng: Rng::Chain(ChainRng::default()),
+            shootout: None,
+            rules: MatchRules::default(),
+            box_score,
+            last_pass_player_id: None,
+            is_aborted: false,
+            last_active_account: None,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VGame {
+    Current(Game),
+}
+
+impl From<VGame> for Game {
+    fn from(v_game: VGame) -> Self {
+        match v_game {
+            VGame::Current(game) => game,
+        }
+    }
 }
 
 impl Game {
@@ -72,11 +198,15 @@ impl Game {
         account_id_1: AccountId,
         account_id_2: AccountId,
         reward: TokenBalance,
-        game_id: &GameId
+        game_id: &GameId,
+        rng: Rng,
+        rules: MatchRules
     ) -> Game {
         let team1 = team_metadata_to_team(teams.0, 1);
         let team2 = team_metadata_to_team(teams.1, 2);
 
+        let box_score = BoxScore::new(account_id_1.clone(), account_id_2.clone());
+
         let user_info1 = UserInfo {
             user_id: USER_ID1,
             team: team1,
@@ -84,6 +214,8 @@ impl Game {
             take_to_called: false,
             coach_speech_called: false,
             is_goalie_out: false,
+            shootout_shooters: vec![],
+            autopilot: None,
         };
 
         let user_info2 = UserInfo {
@@ -93,6 +225,8 @@ impl Game {
             take_to_called: false,
             coach_speech_called: false,
             is_goalie_out: false,
+            shootout_shooters: vec![],
+            autopilot: None,
         };
 
         let game = Game {
@@ -108,16 +242,21 @@ impl Game {
             last_action: StartGame { action_type: ActionTypes::StartGame },
             last_event_generation_time: env::block_timestamp(),
             number_of_generated_events_in_current_block: 0,
-            max_number_of_generated_events_in_block: 2
+            max_number_of_generated_events_in_block: rules.max_number_of_generated_events_in_block,
+            rng,
+            shootout: None,
+            rules,
+            box_score,
+            last_pass_player_id: None,
+            is_aborted: false,
+            last_active_account: None,
         };
 
         game
     }
 
-    pub fn get_random_in_range(min: usize, max: usize, index: usize) -> usize {
-        let random = *env::random_seed().get(index).unwrap();
-        let random_in_range = (random as f64 / 256.0) * (max - min) as f64 + min as f64;
-        random_in_range.floor() as usize
+    pub fn get_random_in_range(&self, min: usize, max: usize) -> usize {
+        self.rng.get_random_in_range(min, max)
     }
 }
 
@@ -384,7 +523,8 @@ impl Game {
             match action {
                 TakeTO {..} | CoachSpeech {..} | GoalieBack {..}
                 | GoalieOut {..} | EndedPenalty {..} | DelayedPenaltySignal {..}
-                | Penalty {..} => {},
+                | Penalty {..} | Overtime {..} | Shootout {..}
+                | ShootoutAttempt {..} => {},
                 _ => {
                     self.last_action = action.clone();
                 }
@@ -424,16 +564,74 @@ impl Game {
         } else {
             let state = GameState::InProgress;
 
-            if self.turns == THIRD_PERIOD {
+            if self.turns == self.rules.third_period {
+                self.start_overtime();
                 (state, Some(Overtime { action_type: ActionTypes::Overtime }))
+            } else if self.turns == self.rules.overtime_period && self.shootout.is_none() {
+                self.start_shootout();
+                (state, Some(Shootout { action_type: ActionTypes::Shootout }))
             } else {
                 (state, None)
             }
         };
     }
 
+    pub fn assert_not_finished(&self) {
+        assert!(self.winner_index.is_none() && !self.is_aborted, "Game already finished");
+    }
+
+    /// Events generated by someone who does not play the game do not count
+    pub fn record_activity(&mut self, account_id: &AccountId) {
+        if self.user1.account_id == *account_id || self.user2.account_id == *account_id {
+            self.last_active_account = Some(account_id.clone());
+        }
+    }
+
+    pub fn is_overtime(&self) -> bool {
+        self.turns >= self.rules.third_period
+    }
+
+    /// 1-3 for the regular periods, 4 for overtime and the shootout
+    pub fn get_period(&self) -> u8 {
+        if self.turns < self.rules.first_period {
+            1
+        } else if self.turns < self.rules.second_period {
+            2
+        } else if self.turns < self.rules.third_period {
+            3
+        } else {
+            4
+        }
+    }
+
+    fn start_overtime(&mut self) {
+        self.user1.team.set_overtime_fives();
+        self.user2.team.set_overtime_fives();
+    }
+
+    fn start_shootout(&mut self) {
+        self.player_with_puck = None;
+        self.shootout = Some(ShootoutState {
+            next_user_id: USER_ID1,
+            goals1: 0,
+            goals2: 0,
+            shooters1: vec![],
+            shooters2: vec![],
+        });
+    }
+
     pub fn step(&mut self) -> Vec<ActionData> {
-        let mut actions = self.do_action();
+        if self.shootout.is_some() {
+            self.event_generation_delay += self.rules.penalty_shot_delay * SECOND;
+            return self.do_shootout_attempt();
+        }
+
+        let mut actions = if self.is_stoppage() {
+            self.run_autopilot()
+        } else {
+            vec![]
+        };
+        actions.append(&mut self.do_action());
 
         self.increase_five_time_field();
         match self.last_action {
@@ -461,13 +659,20 @@ impl Game {
         actions
     }
 
+    /// The play is stopped and the next action is a face-off
+    fn is_stoppage(&self) -> bool {
+        matches!(self.last_action,
+            StartGame { .. } | EndOfPeriod { .. } | Goal { .. } | PuckOut { .. } | NetOff { .. } | Offside { .. }
+            | Save { .. } | Icing { .. } | Penalty { .. } | Fight { .. } | FightWon { .. })
+    }
+
     fn do_action(&mut self) -> Vec<ActionData> {
         let action = Action;
 
         let actions = match self.last_action {
             StartGame { .. } => {
                 self.zone_number = 2;
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 let mut actions = vec![
                     StartGame {
@@ -483,12 +688,12 @@ impl Game {
                 actions
             },
             EndOfPeriod { .. } => {
-                let mut actions = if self.turns == FIRST_PERIOD {
+                let mut actions = if self.turns == self.rules.first_period {
                     vec![StartPeriod {
                         action_type: ActionTypes::StartPeriod,
                         number: 2,
                     }]
-                } else if self.turns == SECOND_PERIOD {
+                } else if self.turns == self.rules.second_period {
                     vec![StartPeriod {
                         action_type: ActionTypes::StartPeriod,
                         number: 3,
@@ -498,7 +703,7 @@ impl Game {
                 };
 
                 self.zone_number = 2;
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
                 actions.append(&mut self.check_and_do_penalties());
@@ -509,7 +714,7 @@ impl Game {
             },
             Goal { .. } => {
                 self.zone_number = 2;
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
 
@@ -523,7 +728,7 @@ impl Game {
             },
             PuckOut { .. } | NetOff { .. } => {
                 let random_position = self.get_random_position();
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
 
@@ -537,7 +742,7 @@ impl Game {
             },
             Offside { .. } => {
                 let random_position = self.get_random_position_after_offside();
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
 
@@ -550,7 +755,7 @@ impl Game {
                 actions
             }
             Save { .. } => {
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.swap_all_players_in_fives();
                 self.face_off_after_save()
@@ -563,7 +768,7 @@ impl Game {
                 };
 
                 let random_position = self.get_random_position();
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
                 let mut actions = Vec::new();
@@ -582,7 +787,7 @@ impl Game {
                 };
 
                 let random_position = self.get_random_position();
-                self.event_generation_delay += 3 * SECOND;
+                self.event_generation_delay += self.rules.face_off_delay * SECOND;
 
                 self.player_with_puck = None;
                 let mut actions = Vec::new();
@@ -594,7 +799,7 @@ impl Game {
                 actions
             },
             PenaltyShot { .. } => {
-                self.event_generation_delay += 5 * SECOND;
+                self.event_generation_delay += self.rules.penalty_shot_delay * SECOND;
                 self.do_penalty_shot()
             }
 
@@ -653,10 +858,10 @@ impl Game {
         let mut actions = Vec::new();
 
         let players_to_big_penalty = self.get_players_to_big_penalty(&penalty_user_id);
-        actions.append(&mut self.dp(players_to_big_penalty, &user_id, &penalty_user_id, BIG_PENALTY));
+        actions.append(&mut self.dp(players_to_big_penalty, &user_id, &penalty_user_id, self.rules.big_penalty));
 
         let players_to_small_penalty = self.get_players_to_small_penalty(&penalty_user_id);
-        actions.append(&mut self.dp(players_to_small_penalty, &user_id, &penalty_user_id, SMALL_PENALTY));
+        actions.append(&mut self.dp(players_to_small_penalty, &user_id, &penalty_user_id, self.rules.small_penalty));
 
         self.clear_players_to_penalties(&penalty_user_id);
 
@@ -707,7 +912,7 @@ impl Game {
         user_id: &UserId,
         penalty_user_id: &UserId
     ) -> ActionData {
-        let action_type = if penalty_time == BIG_PENALTY {
+        let action_type = if penalty_time == self.rules.big_penalty {
             BigPenalty
         } else {
             SmallPenalty
@@ -726,6 +931,7 @@ impl Game {
         };
 
         self.penalty_player(penalty_time, penalty_player_id, penalty_user_id);
+        self.record_penalty(*penalty_user_id, penalty_player_id, penalty_time);
 
         let penalty_user_mut = self.get_user_info_mut(penalty_user_id);
         penalty_user_mut.team.do_penalty(&penalty_player_id);
@@ -763,23 +969,23 @@ impl Game {
     }
 
     fn do_penalty_shot(&mut self) -> Vec<ActionData> {
-        let player_with_puck = self.get_player_with_puck();
-        let player_stat = (player_with_puck.stats.get_skating()
-            + player_with_puck.stats.get_shooting()
-            + player_with_puck.stats.morale as f32
-            + player_with_puck.stats.get_iq()) / 4.0;
-
-        let (user_id, _player_id) = self.player_with_puck.clone()
+        let (user_id, player_id) = self.player_with_puck.clone()
             .expect("Cannot find player with puck");
 
-        let user_opponent = self.get_opponent_info(user_id);
-        let number_goalie = user_opponent.team.active_goalie.clone();
-        let opponent_goalie = user_opponent.team.goalies.get(&number_goalie).unwrap();
+        let (is_goal, shot_type, shot_zone) = self.take_penalty_shot(user_id, &player_id);
+
+        self.record_shot(user_id, &player_id);
+        if is_goal {
+            self.record_goal(user_id, &player_id, None, true);
+        } else {
+            self.record_save(user_id);
+        }
 
-        let goalie_stat = (opponent_goalie.stats.get_reflexes()
-            + opponent_goalie.stats.morale as f32) / 2.0;
+        let player_with_puck = self.get_player_with_puck();
+        let user_opponent = self.get_opponent_info(user_id);
+        let opponent_goalie = user_opponent.team.get_active_goalie();
 
-        return if has_won(player_stat, goalie_stat) {
+        return if is_goal {
             let user = self.get_user_info(user_id);
             let action = vec![Goal {
                 action_type: ActionTypes::Goal,
@@ -788,7 +994,9 @@ impl Game {
                 player_img: player_with_puck.img.clone().expect("Player img not found"),
                 player_number1: player_with_puck.number,
                 player_name2: None,
-                player_number2: None
+                player_number2: None,
+                shot_type,
+                shot_zone,
             }];
 
             self.get_user_info_mut(&user_id).team.score += 1;
@@ -799,10 +1007,109 @@ impl Game {
                 action_type: ActionTypes::Save,
                 account_id: user_opponent.account_id.clone(),
                 goalie_number: opponent_goalie.number,
+                shot_type,
+                shot_zone,
             }]
         }
     }
 
+    /// One-on-one of the player against the opponent goalie. Returns whether it is a goal
+    fn take_penalty_shot(&self, user_id: UserId, player_id: &TokenId) -> (bool, ShotType, ShotZone) {
+        let player = self.get_user_info(user_id).team.get_field_player(player_id);
+        let shot_type = ShotType::choose_without_pass(self, &player.stats);
+        let shot_zone = ShotZone::choose(self);
+
+        let player_stat = (player.stats.get_skating()
+            + shot_type.get_stat(&player.stats)
+            + player.stats.morale as f32
+            + player.stats.get_iq()) / 4.0;
+
+        let opponent_goalie = self.get_opponent_info(user_id).team.get_active_goalie();
+        let reflexes = (opponent_goalie.stats.get_reflexes()
+            + shot_zone.get_goalie_stat(&opponent_goalie.stats)) / 2.0;
+        let goalie_stat = (reflexes + opponent_goalie.stats.morale as f32) / 2.0;
+
+        (has_won(self, player_stat, goalie_stat), shot_type, shot_zone)
+    }
+
+    fn do_shootout_attempt(&mut self) -> Vec<ActionData> {
+        let mut shootout = self.shootout.clone().expect("Shootout not started");
+        let user_id = shootout.next_user_id;
+
+        let player_id = self.get_shootout_shooter(user_id, &shootout);
+        let (is_goal, ..) = self.take_penalty_shot(user_id, &player_id);
+
+        let (goals, shooters) = if user_id == USER_ID1 {
+            (&mut shootout.goals1, &mut shootout.shooters1)
+        } else {
+            (&mut shootout.goals2, &mut shootout.shooters2)
+        };
+
+        shooters.push(player_id.clone());
+        if is_goal {
+            *goals += 1;
+        }
+        let round = shooters.len() as u8;
+
+        shootout.next_user_id = if user_id == USER_ID1 { USER_ID2 } else { USER_ID1 };
+
+        let user = self.get_user_info(user_id);
+        let player = user.team.get_field_player(&player_id);
+        let opponent_goalie = self.get_opponent_info(user_id).team.get_active_goalie();
+
+        let action = ShootoutAttempt {
+            action_type: ActionTypes::ShootoutAttempt,
+            account_id: user.account_id.clone(),
+            player_name: player.name.clone().expect("Player name not found"),
+            player_img: player.img.clone().expect("Player img not found"),
+            player_number: player.number,
+            goalie_number: opponent_goalie.number,
+            round,
+            is_goal,
+        };
+
+        // The decisive goal of the shootout is added to the score
+        if let Some(winner_id) = shootout.get_winner() {
+            self.get_user_info_mut(&winner_id).team.score += 1;
+        }
+        self.shootout = Some(shootout);
+
+        vec![action]
+    }
+
+    /// Shooters chosen by the manager go first, then the best shooters.
+    /// Nobody shoots twice until every player has shot
+    fn get_shootout_shooter(&self, user_id: UserId, shootout: &ShootoutState) -> TokenId {
+        let user = self.get_user_info(user_id);
+        let shooters = if user_id == USER_ID1 {
+            &shootout.shooters1
+        } else {
+            &shootout.shooters2
+        };
+
+        let number_of_shots_in_cycle = shooters.len() % user.team.field_players.len();
+        let shooters_in_cycle = &shooters[shooters.len() - number_of_shots_in_cycle..];
+
+        let mut candidates: Vec<TokenId> = user.shootout_shooters.iter()
+            .filter(|player_id| user.team.field_players.contains_key(*player_id))
+            .cloned()
+            .collect();
+
+        let mut field_players: Vec<(&TokenId, &FieldPlayer)> = user.team.field_players.iter().collect();
+        field_players.sort_by(|(_, player1), (_, player2)|
+            player2.stats.get_shooting().partial_cmp(&player1.stats.get_shooting()).unwrap());
+
+        for (player_id, _) in field_players {
+            if !candidates.contains(player_id) {
+                candidates.push(player_id.clone());
+            }
+        }
+
+        candidates.into_iter()
+            .find(|player_id| !shooters_in_cycle.contains(player_id))
+            .expect("Shooter not found")
+    }
+
     fn get_random_position(&self) -> PlayerPosition {
         let positions = match self.zone_number {
             1 => vec![LeftDefender, RightDefender],
@@ -811,7 +1118,7 @@ impl Game {
             _ => panic!("Undefined zone number")
         };
 
-        let rnd = Game::get_random_in_range(1, positions.len(), 22);
+        let rnd = self.get_random_in_range(1, positions.len());
 
         positions[rnd]
     }
@@ -824,7 +1131,7 @@ impl Game {
             vec![LeftDefender, RightDefender]
         };
 
-        let rnd = Game::get_random_in_range(1, positions.len(), 22);
+        let rnd = self.get_random_in_range(1, positions.len());
 
         positions[rnd]
     }
@@ -895,7 +1202,7 @@ impl Game {
             player_position2: opponent_pos.1.clone(),
         }];
 
-        if has_won(compared_stat1, compared_stat2) {
+        if has_won(self, compared_stat1, compared_stat2) {
             actions.push(FaceOffWin {
                 action_type: ActionTypes::FaceOffWin,
                 account_id: user.account_id.clone(),
@@ -917,6 +1224,9 @@ impl Game {
             self.player_with_puck = Option::from((player2.get_user_id(), player2.get_player_id()));
         }
 
+        let (winner_user_id, winner_id) = self.get_player_id_with_puck();
+        self.get_skater_box_score_mut(winner_user_id, &winner_id).faceoffs_won += 1;
+
         actions
     }
 
@@ -926,6 +1236,9 @@ impl Game {
 
         let five2 = self.user2.team.get_active_five_mut();
         five2.time_field = Some(five2.time_field.unwrap() + 1);
+
+        self.record_time_on_ice(USER_ID1);
+        self.record_time_on_ice(USER_ID2);
     }
 
     fn check_teams_to_change_active_five(&mut self) {
@@ -940,13 +1253,14 @@ impl Game {
     }
 
     fn check_end_of_period(&mut self) -> Option<ActionData> {
-        if [FIRST_PERIOD, SECOND_PERIOD, THIRD_PERIOD].contains(&self.turns) {
-            let period = if self.turns >= THIRD_PERIOD {
+        let rules = &self.rules;
+        if [rules.first_period, rules.second_period, rules.third_period].contains(&self.turns) {
+            let period = if self.turns >= rules.third_period {
                 3
-            } else if self.turns >= SECOND_PERIOD {
+            } else if self.turns >= rules.second_period {
                 2
             } else {
-                FIRST_PERIOD
+                rules.first_period
             };
 
             self.swap_users();
@@ -958,7 +1272,7 @@ impl Game {
     }
 
     fn is_game_over(&self) -> bool {
-        if self.turns >= THIRD_PERIOD && self.user1.team.score != self.user2.team.score {
+        if self.turns >= self.rules.third_period && self.user1.team.score != self.user2.team.score {
             true
         } else {
             false
@@ -1114,4 +1428,94 @@ impl Game {
             goalie.user_id = Some(USER_ID2);
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::bot::{generate_bot_team, BotDifficulty};
+    use crate::game::match_rules::MatchRules;
+    use crate::test_utils::{get_game_with_teams, set_context};
+
+    fn get_shootout(goals: (u8, u8), attempts: (usize, usize)) -> ShootoutState {
+        ShootoutState {
+            next_user_id: USER_ID1,
+            goals1: goals.0,
+            goals2: goals.1,
+            shooters1: (0..attempts.0).map(|number| number.to_string()).collect(),
+            shooters2: (0..attempts.1).map(|number| number.to_string()).collect(),
+        }
+    }
+
+    /// Overtime needs all four fives
+    fn get_full_game() -> Game {
+        let teams = (generate_bot_team(BotDifficulty::Medium), generate_bot_team(BotDifficulty::Medium));
+        get_game_with_teams(1, teams, MatchRules::default())
+    }
+
+    #[test]
+    fn shootout_is_decided_when_one_team_cannot_catch_up() {
+        assert_eq!(get_shootout((0, 0), (1, 1)).get_winner(), None);
+        assert_eq!(get_shootout((2, 0), (2, 1)).get_winner(), None);
+        assert_eq!(get_shootout((2, 0), (2, 2)).get_winner(), Some(USER_ID1));
+        assert_eq!(get_shootout((0, 2), (3, 2)).get_winner(), Some(USER_ID2));
+        assert_eq!(get_shootout((1, 1), (3, 3)).get_winner(), None);
+    }
+
+    #[test]
+    fn sudden_death_is_decided_after_a_pair_of_attempts() {
+        assert_eq!(get_shootout((2, 1), (4, 3)).get_winner(), None);
+        assert_eq!(get_shootout((2, 1), (4, 4)).get_winner(), Some(USER_ID1));
+        assert_eq!(get_shootout((2, 3), (5, 5)).get_winner(), Some(USER_ID2));
+        assert_eq!(get_shootout((2, 2), (4, 4)).get_winner(), None);
+    }
+
+    #[test]
+    fn lead_after_regulation_ends_the_game() {
+        set_context(accounts(0));
+        let mut game = get_full_game();
+        game.turns = game.rules.third_period;
+        game.user2.team.score = 1;
+
+        assert_eq!(game.get_game_state().0, GameState::GameOver { winner_id: USER_ID2 });
+    }
+
+    #[test]
+    fn tie_after_regulation_goes_to_three_on_three() {
+        set_context(accounts(0));
+        let mut game = get_full_game();
+        game.turns = game.rules.third_period;
+
+        let (state, action) = game.get_game_state();
+        assert_eq!(state, GameState::InProgress);
+        assert!(matches!(action, Some(Overtime { .. })));
+        assert_eq!(game.get_period(), 4);
+
+        for user in [&game.user1, &game.user2] {
+            let first_five = &user.team.fives[&First].field_players;
+            assert!(!first_five.contains_key(&LeftWing) && !first_five.contains_key(&RightWing));
+            assert_eq!(user.team.active_five.field_players.len(), 3);
+        }
+    }
+
+    #[test]
+    fn tie_after_overtime_is_decided_by_shootout() {
+        set_context(accounts(0));
+        let mut game = get_full_game();
+        game.turns = game.rules.overtime_period;
+
+        let (state, action) = game.get_game_state();
+        assert_eq!(state, GameState::InProgress);
+        assert!(matches!(action, Some(Shootout { .. })));
+
+        while game.get_game_state().0 == GameState::InProgress {
+            set_context(accounts(0));
+            let actions = game.step();
+            assert!(matches!(actions[..], [ShootoutAttempt { .. }]));
+        }
+
+        let shootout = game.shootout.clone().unwrap();
+        assert!(shootout.shooters1.len() == shootout.shooters2.len() || shootout.shooters1.len() == shootout.shooters2.len() + 1);
+        assert_eq!((game.user1.team.score as i16 - game.user2.team.score as i16).abs(), 1);
+    }
+}
@@ -0,0 +1,252 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::game::actions::random_actions::{BIG_PENALTY, SMALL_PENALTY};
+use crate::game::game::{FIRST_PERIOD, OVERTIME_PERIOD, SECOND_PERIOD, THIRD_PERIOD};
+
+// Default delays, in seconds
+const FACE_OFF_DELAY: u64 = 3;
+const PENALTY_SHOT_DELAY: u64 = 5;
+const BLOCK_LIMIT_DELAY: u64 = 1;
+const INACTIVITY_TIMEOUT: u64 = 600;
+// Longer ones would lock the stakes for too long
+const MAX_DELAY: u64 = 60 * 60;
+const MAX_INACTIVITY_TIMEOUT: u64 = 24 * 60 * 60;
+
+const MAX_NUMBER_OF_GENERATED_EVENTS_IN_BLOCK: u8 = 2;
+
+/// Length of a match. Periods are the numbers of turns at which they end
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MatchRules {
+    pub(crate) first_period: u8,
+    pub(crate) second_period: u8,
+    pub(crate) third_period: u8,
+    pub(crate) overtime_period: u8,
+
+    // number of events
+    pub(crate) big_penalty: u8,
+    pub(crate) small_penalty: u8,
+
+    pub(crate) max_number_of_generated_events_in_block: u8,
+
+    // in seconds
+    pub(crate) face_off_delay: u64,
+    pub(crate) penalty_shot_delay: u64,
+    pub(crate) block_limit_delay: u64,
+    // Time without events after which anyone can claim the game
+    #[serde(default = "default_inactivity_timeout")]
+    pub(crate) inactivity_timeout: u64,
+}
+
+fn default_inactivity_timeout() -> u64 {
+    INACTIVITY_TIMEOUT
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            first_period: FIRST_PERIOD,
+            second_period: SECOND_PERIOD,
+            third_period: THIRD_PERIOD,
+            overtime_period: OVERTIME_PERIOD,
+            big_penalty: BIG_PENALTY,
+            small_penalty: SMALL_PENALTY,
+            max_number_of_generated_events_in_block: MAX_NUMBER_OF_GENERATED_EVENTS_IN_BLOCK,
+            face_off_delay: FACE_OFF_DELAY,
+            penalty_shot_delay: PENALTY_SHOT_DELAY,
+            block_limit_delay: BLOCK_LIMIT_DELAY,
+            inactivity_timeout: INACTIVITY_TIMEOUT,
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn assert_valid(&self) {
+        assert!(0 < self.first_period
+                    && self.first_period < self.second_period
+                    && self.second_period < self.third_period
+                    && self.third_period < self.overtime_period,
+                "Periods must end one after another");
+
+        assert!(0 < self.small_penalty && self.small_penalty < self.big_penalty,
+                "A big penalty must be longer than a small one");
+
+        assert!(self.max_number_of_generated_events_in_block > 0,
+                "At least one event per block is required");
+
+        assert!(self.face_off_delay <= MAX_DELAY
+                    && self.penalty_shot_delay <= MAX_DELAY
+                    && self.block_limit_delay <= MAX_DELAY,
+                "Delays must not be longer than {} seconds", MAX_DELAY);
+        assert!(self.inactivity_timeout <= MAX_INACTIVITY_TIMEOUT,
+                "Inactivity timeout must not be longer than {} seconds", MAX_INACTIVITY_TIMEOUT);
+
+        assert!(self.inactivity_timeout > self.face_off_delay + self.penalty_shot_delay + self.block_limit_delay,
+                "Inactivity timeout must be longer than the delays between events");
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MatchRulesV1 {
+    first_period: u8,
+    second_period: u8,
+    third_period: u8,
+    overtime_period: u8,
+    big_penalty: u8,
+    small_penalty: u8,
+    max_number_of_generated_events_in_block: u8,
+    face_off_delay: u64,
+    penalty_shot_delay: u64,
+    block_limit_delay: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VMatchRules {
+    V1(MatchRulesV1),
+    Current(MatchRules),
+}
+
+impl From<VMatchRules> for MatchRules {
+    fn from(v_match_rules: VMatchRules) -> Self {
+        match v_match_rules {
+            VMatchRules::V1(match_rules) => MatchRules {
+                first_period: match_rules.first_period,
+                second_period: match_rules.second_period,
+                third_period: match_rules.third_period,
+                overtime_period: match_rules.overtime_period,
+                big_penalty: match_rules.big_penalty,
+                small_penalty: match_rules.small_penalty,
+                max_number_of_generated_events_in_block: match_rules.max_number_of_generated_events_in_block,
+                face_off_delay: match_rules.face_off_delay,
+                penalty_shot_delay: match_rules.penalty_shot_delay,
+                block_limit_delay: match_rules.block_limit_delay,
+                inactivity_timeout: INACTIVITY_TIMEOUT,
+            },
+            VMatchRules::Current(match_rules) => match_rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::manager::GameConfig;
+    use crate::test_utils::{get_game_with_teams, get_team_metadata, set_context};
+
+    fn get_short_rules() -> MatchRules {
+        MatchRules {
+            first_period: 5,
+            second_period: 10,
+            third_period: 15,
+            overtime_period: 20,
+            ..MatchRules::default()
+        }
+    }
+
+    #[test]
+    fn default_rules_are_valid() {
+        MatchRules::default().assert_valid();
+        get_short_rules().assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Periods must end one after another")]
+    fn periods_out_of_order() {
+        MatchRules { second_period: 80, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "A big penalty must be longer than a small one")]
+    fn small_penalty_longer_than_big() {
+        MatchRules { small_penalty: BIG_PENALTY, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Inactivity timeout must be longer than the delays between events")]
+    fn inactivity_timeout_shorter_than_delays() {
+        MatchRules { inactivity_timeout: FACE_OFF_DELAY, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    fn longest_delays_and_timeout() {
+        MatchRules {
+            face_off_delay: MAX_DELAY,
+            penalty_shot_delay: MAX_DELAY,
+            block_limit_delay: MAX_DELAY,
+            inactivity_timeout: MAX_INACTIVITY_TIMEOUT,
+            ..MatchRules::default()
+        }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Delays must not be longer than 3600 seconds")]
+    fn face_off_delay_too_long() {
+        MatchRules { face_off_delay: 20_000_000_000, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Delays must not be longer than 3600 seconds")]
+    fn block_limit_delay_too_long() {
+        MatchRules { block_limit_delay: MAX_DELAY + 1, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "Inactivity timeout must not be longer than 86400 seconds")]
+    fn inactivity_timeout_too_long() {
+        MatchRules { inactivity_timeout: u64::MAX, ..MatchRules::default() }.assert_valid();
+    }
+
+    #[test]
+    fn first_rules_get_default_timeout() {
+        let rules = MatchRulesV1 {
+            first_period: 5,
+            second_period: 10,
+            third_period: 15,
+            overtime_period: 20,
+            big_penalty: BIG_PENALTY,
+            small_penalty: SMALL_PENALTY,
+            max_number_of_generated_events_in_block: 1,
+            face_off_delay: FACE_OFF_DELAY,
+            penalty_shot_delay: PENALTY_SHOT_DELAY,
+            block_limit_delay: BLOCK_LIMIT_DELAY,
+        };
+
+        let rules: MatchRules = VMatchRules::V1(rules).into();
+        assert_eq!(rules, MatchRules { max_number_of_generated_events_in_block: 1, ..get_short_rules() });
+    }
+
+    #[test]
+    fn config_without_rules_plays_a_full_match() {
+        let config = GameConfig {
+            deposit: None,
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+
+        assert_eq!(config.get_rules(), MatchRules::default());
+    }
+
+    #[test]
+    fn periods_follow_the_rules() {
+        set_context(accounts(0));
+        let mut game = get_game_with_teams(1, (get_team_metadata("a"), get_team_metadata("b")), get_short_rules());
+
+        let periods: Vec<u8> = [0, 4, 5, 10, 14, 15].iter()
+            .map(|turns| {
+                game.turns = *turns;
+                game.get_period()
+            })
+            .collect();
+
+        assert_eq!(periods, vec![1, 1, 2, 3, 3, 4]);
+        assert!(game.is_overtime());
+    }
+}
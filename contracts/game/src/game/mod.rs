@@ -1,2 +1,6 @@
 pub mod game;
-pub mod actions;
\ No newline at end of file
+pub mod actions;
+pub mod rng;
+pub mod match_rules;
+pub mod box_score;
+pub mod autopilot;
\ No newline at end of file
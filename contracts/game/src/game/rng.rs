@@ -0,0 +1,152 @@
+use std::cell::Cell;
+use std::io::{Error, Write};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub trait GameRng {
+    /// Returns a new pseudo-random value on every call
+    fn next_u64(&self) -> u64;
+
+    /// Random number in [min, max)
+    fn get_random_in_range(&self, min: usize, max: usize) -> usize {
+        let random = self.next_u64() as f64 / (u64::MAX as f64 + 1.0);
+        let random_in_range = random * (max - min) as f64 + min as f64;
+        random_in_range.floor() as usize
+    }
+}
+
+fn hash_with_counter(seed: &[u8], counter: &Cell<u64>) -> u64 {
+    let draw = counter.get();
+    counter.set(draw + 1);
+
+    let mut input = seed.to_vec();
+    input.extend_from_slice(&draw.to_le_bytes());
+
+    let hash = env::sha256(&input);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Hashes the block random seed with the number of draws made so far in the game,
+/// so every draw in the same block gets its own value
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChainRng {
+    counter: Cell<u64>,
+}
+
+impl GameRng for ChainRng {
+    fn next_u64(&self) -> u64 {
+        hash_with_counter(&env::random_seed(), &self.counter)
+    }
+}
+
+/// Deterministic stream: the same seed always produces the same match
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeededRng {
+    seed: Vec<u8>,
+    counter: Cell<u64>,
+}
+
+impl SeededRng {
+    pub fn new(seed: Vec<u8>) -> Self {
+        Self {
+            seed,
+            counter: Cell::new(0),
+        }
+    }
+}
+
+impl GameRng for SeededRng {
+    fn next_u64(&self) -> u64 {
+        hash_with_counter(&self.seed, &self.counter)
+    }
+}
+
+impl BorshSerialize for ChainRng {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        BorshSerialize::serialize(&self.counter.get(), writer)
+    }
+}
+
+impl BorshDeserialize for ChainRng {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, Error> {
+        Ok(Self { counter: Cell::new(<u64 as BorshDeserialize>::deserialize(buf)?) })
+    }
+}
+
+impl BorshSerialize for SeededRng {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        BorshSerialize::serialize(&self.seed, writer)?;
+        BorshSerialize::serialize(&self.counter.get(), writer)
+    }
+}
+
+impl BorshDeserialize for SeededRng {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            seed: <Vec<u8> as BorshDeserialize>::deserialize(buf)?,
+            counter: Cell::new(<u64 as BorshDeserialize>::deserialize(buf)?),
+        })
+    }
+}
+
+/// The random source stored on `Game`
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Rng {
+    Chain(ChainRng),
+    Seeded(SeededRng),
+}
+
+impl GameRng for Rng {
+    fn next_u64(&self) -> u64 {
+        match self {
+            Rng::Chain(rng) => rng.next_u64(),
+            Rng::Seeded(rng) => rng.next_u64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(rng: &impl GameRng, count: usize) -> Vec<u64> {
+        (0..count).map(|_| rng.next_u64()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_stream() {
+        let first = SeededRng::new(vec![1, 2, 3]);
+        let second = SeededRng::new(vec![1, 2, 3]);
+
+        assert_eq!(draw(&first, 10), draw(&second, 10));
+        assert_ne!(draw(&SeededRng::new(vec![1, 2, 4]), 10), draw(&SeededRng::new(vec![1, 2, 3]), 10));
+    }
+
+    #[test]
+    fn stored_rng_continues_the_stream() {
+        let rng = Rng::Seeded(SeededRng::new(vec![7]));
+        let expected = draw(&Rng::Seeded(SeededRng::new(vec![7])), 6);
+
+        let mut values = draw(&rng, 3);
+        let restored = Rng::try_from_slice(&rng.try_to_vec().unwrap()).unwrap();
+        values.append(&mut draw(&restored, 3));
+
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn random_in_range_is_bounded() {
+        let rng = SeededRng::new(vec![0]);
+        for _ in 0..1_000 {
+            let value = rng.get_random_in_range(1, 6);
+            assert!((1..6).contains(&value));
+        }
+    }
+}
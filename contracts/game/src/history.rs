@@ -0,0 +1,235 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::Timestamp;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// A finished game from the side of one account
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct GameResult {
+    pub(crate) game_id: GameId,
+    pub(crate) opponent_id: AccountId,
+    pub(crate) score: u8,
+    pub(crate) opponent_score: u8,
+    pub(crate) is_won: bool,
+    // NEAR if not set
+    pub(crate) token_id: Option<AccountId>,
+    pub(crate) stake: Balance,
+    pub(crate) reward: Balance,
+    pub(crate) finished_at: Timestamp,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VGameResult {
+    Current(GameResult),
+}
+
+impl From<VGameResult> for GameResult {
+    fn from(v_game_result: VGameResult) -> Self {
+        match v_game_result {
+            VGameResult::Current(game_result) => game_result,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameResultOutput {
+    game_id: GameId,
+    opponent_id: AccountId,
+    score: u8,
+    opponent_score: u8,
+    is_won: bool,
+    token_id: Option<AccountId>,
+    stake: U128,
+    reward: U128,
+    finished_at: Timestamp,
+}
+
+impl From<GameResult> for GameResultOutput {
+    fn from(game_result: GameResult) -> Self {
+        GameResultOutput {
+            game_id: game_result.game_id,
+            opponent_id: game_result.opponent_id,
+            score: game_result.score,
+            opponent_score: game_result.opponent_score,
+            is_won: game_result.is_won,
+            token_id: game_result.token_id,
+            stake: U128::from(game_result.stake),
+            reward: U128::from(game_result.reward),
+            finished_at: game_result.finished_at,
+        }
+    }
+}
+
+/// Games of an account against one opponent
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct HeadToHead {
+    pub(crate) games_num: u32,
+    pub(crate) victories_num: u32,
+    pub(crate) goals_for: u32,
+    pub(crate) goals_against: u32,
+    pub(crate) last_game_id: Option<GameId>,
+}
+
+impl HeadToHead {
+    fn add_game(&mut self, game_result: &GameResult) {
+        self.games_num += 1;
+        if game_result.is_won {
+            self.victories_num += 1;
+        }
+        self.goals_for += game_result.score as u32;
+        self.goals_against += game_result.opponent_score as u32;
+        self.last_game_id = Some(game_result.game_id);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HeadToHeadOutput {
+    account_id: AccountId,
+    opponent_id: AccountId,
+    games_num: u32,
+    victories_num: u32,
+    defeats_num: u32,
+    goals_for: u32,
+    goals_against: u32,
+    last_game_id: Option<GameId>,
+}
+
+impl Hockey {
+    /// Records the game in the histories of both players
+    pub(crate) fn internal_record_game_results(&mut self, game: &Game, winner_index: UserId, reward: Balance) {
+        let token_id = game.reward.get_ft_token_id();
+        let stake = game.reward.balance / 2;
+        let finished_at = env::block_timestamp();
+
+        for (user, opponent) in [(&game.user1, &game.user2), (&game.user2, &game.user1)] {
+            let is_won = user.user_id == winner_index;
+            let game_result = GameResult {
+                game_id: game.game_id,
+                opponent_id: opponent.account_id.clone(),
+                score: user.team.score,
+                opponent_score: opponent.team.score,
+                is_won,
+                token_id: token_id.clone(),
+                stake,
+                reward: if is_won { reward } else { 0 },
+                finished_at,
+            };
+
+            let key = (user.account_id.clone(), opponent.account_id.clone());
+            let mut head_to_head = self.head_to_head.get(&key).unwrap_or_default();
+            head_to_head.add_game(&game_result);
+            self.head_to_head.insert(&key, &head_to_head);
+
+            let mut match_history = self.match_history.get(&user.account_id).unwrap_or_else(|| {
+                Vector::new(StorageKey::MatchHistoryPerAccount { account_id: hash_account_id(user.account_id.as_str()) }.try_to_vec().unwrap())
+            });
+            match_history.push(&VGameResult::Current(game_result));
+            self.match_history.insert(&user.account_id, &match_history);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// The latest games first
+    pub fn get_match_history(&self, account_id: AccountId, from_index: u64, limit: u64) -> Vec<GameResultOutput> {
+        let match_history = match self.match_history.get(&account_id) {
+            Some(match_history) => match_history,
+            None => return vec![],
+        };
+
+        let len = match_history.len();
+        (from_index..std::cmp::min(from_index + limit, len))
+            .map(|index| GameResult::from(match_history.get(len - 1 - index).unwrap()).into())
+            .collect()
+    }
+
+    /// Record of `account_id` against `opponent_id`
+    pub fn get_head_to_head(&self, account_id: AccountId, opponent_id: AccountId) -> HeadToHeadOutput {
+        let head_to_head = self.head_to_head.get(&(account_id.clone(), opponent_id.clone())).unwrap_or_default();
+
+        HeadToHeadOutput {
+            account_id,
+            opponent_id,
+            games_num: head_to_head.games_num,
+            victories_num: head_to_head.victories_num,
+            defeats_num: head_to_head.games_num - head_to_head.victories_num,
+            goals_for: head_to_head.goals_for,
+            goals_against: head_to_head.goals_against,
+            last_game_id: head_to_head.last_game_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::user_info::{USER_ID1, USER_ID2};
+    use crate::test_utils::{get_game, set_context};
+
+    /// `accounts(1)` scores `score1`, `accounts(2)` scores `score2`
+    fn record_game(hockey: &mut Hockey, game_id: GameId, score1: u8, score2: u8, reward: Balance) {
+        let mut game = get_game(game_id);
+        game.user1.team.score = score1;
+        game.user2.team.score = score2;
+        let winner_index = if score1 > score2 { USER_ID1 } else { USER_ID2 };
+
+        hockey.internal_record_game_results(&game, winner_index, reward);
+    }
+
+    #[test]
+    fn head_to_head_is_kept_for_both_sides() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        record_game(&mut hockey, 1, 3, 1, 0);
+        record_game(&mut hockey, 2, 2, 4, 0);
+        record_game(&mut hockey, 3, 5, 0, 0);
+
+        let head_to_head = hockey.get_head_to_head(accounts(1), accounts(2));
+        assert_eq!((head_to_head.games_num, head_to_head.victories_num, head_to_head.defeats_num), (3, 2, 1));
+        assert_eq!((head_to_head.goals_for, head_to_head.goals_against), (10, 5));
+        assert_eq!(head_to_head.last_game_id, Some(3));
+
+        let head_to_head = hockey.get_head_to_head(accounts(2), accounts(1));
+        assert_eq!((head_to_head.games_num, head_to_head.victories_num, head_to_head.defeats_num), (3, 1, 2));
+        assert_eq!((head_to_head.goals_for, head_to_head.goals_against), (5, 10));
+        assert_eq!(head_to_head.last_game_id, Some(3));
+    }
+
+    #[test]
+    fn no_games_against_the_opponent() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        record_game(&mut hockey, 1, 3, 1, 0);
+
+        let head_to_head = hockey.get_head_to_head(accounts(1), accounts(3));
+        assert_eq!((head_to_head.games_num, head_to_head.defeats_num), (0, 0));
+        assert!(head_to_head.last_game_id.is_none());
+    }
+
+    #[test]
+    fn latest_games_first() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        record_game(&mut hockey, 1, 3, 1, 18);
+        record_game(&mut hockey, 2, 2, 4, 18);
+        record_game(&mut hockey, 3, 5, 0, 18);
+
+        let game_ids: Vec<GameId> = hockey.get_match_history(accounts(1), 0, 10).iter().map(|result| result.game_id).collect();
+        assert_eq!(game_ids, vec![3, 2, 1]);
+
+        let history = hockey.get_match_history(accounts(2), 1, 1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].game_id, 2);
+        assert_eq!(history[0].opponent_id, accounts(1));
+        assert_eq!((history[0].score, history[0].opponent_score), (4, 2));
+        assert_eq!(history[0].reward, U128::from(18));
+
+        assert_eq!(hockey.get_match_history(accounts(2), 0, 10)[0].reward, U128::from(0));
+        assert!(hockey.get_match_history(accounts(1), 3, 10).is_empty());
+        assert!(hockey.get_match_history(accounts(3), 0, 10).is_empty());
+    }
+}
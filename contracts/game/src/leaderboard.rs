@@ -0,0 +1,242 @@
+use crate::*;
+use near_sdk::collections::TreeMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::manager::Stats;
+use crate::owner::MAX_BPS;
+
+// Accounts with fewer games are not ranked by win percentage
+pub const MIN_GAMES_FOR_WIN_RATE: u64 = 10;
+
+pub const LEADERBOARD_TYPES: [LeaderboardType; 5] = [
+    LeaderboardType::Victories,
+    LeaderboardType::WinRate,
+    LeaderboardType::TotalReward,
+    LeaderboardType::AffiliateReward,
+    LeaderboardType::Rating,
+];
+
+/// Rewards are in NEAR. Win rate is in basis points
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LeaderboardType {
+    Victories,
+    WinRate,
+    TotalReward,
+    AffiliateReward,
+    Rating,
+}
+
+// (score, account) -> nothing. Sorted by score
+pub type Leaderboard = TreeMap<(u128, AccountId), ()>;
+
+/// None if the account is not ranked
+fn get_score(stats: &Stats, leaderboard_type: LeaderboardType) -> Option<u128> {
+    let score = match leaderboard_type {
+        LeaderboardType::Victories => stats.victories_num as u128,
+        LeaderboardType::WinRate => {
+            if stats.games_num < MIN_GAMES_FOR_WIN_RATE {
+                return None;
+            }
+            (stats.victories_num * MAX_BPS as u64 / stats.games_num) as u128
+        },
+        LeaderboardType::TotalReward => stats.total_reward.get(&None).unwrap_or(0),
+        LeaderboardType::AffiliateReward => stats.total_affiliate_reward.get(&None).unwrap_or(0),
+        LeaderboardType::Rating => stats.rating as u128,
+    };
+
+    if score > 0 {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+fn get_scores(stats: &Stats) -> Vec<Option<u128>> {
+    LEADERBOARD_TYPES.iter()
+        .map(|leaderboard_type| get_score(stats, *leaderboard_type))
+        .collect()
+}
+
+impl Hockey {
+    /// Current scores of the account. Accounts without stats are not ranked
+    pub(crate) fn internal_get_scores(&self, account_id: &AccountId, stats: &Stats) -> Vec<Option<u128>> {
+        if self.stats.get(account_id).is_some() {
+            get_scores(stats)
+        } else {
+            vec![None; LEADERBOARD_TYPES.len()]
+        }
+    }
+
+    fn internal_get_leaderboard(&self, leaderboard_type: LeaderboardType) -> Leaderboard {
+        self.leaderboards.get(&leaderboard_type).unwrap_or_else(|| {
+            TreeMap::new(StorageKey::Leaderboard { leaderboard_type }.try_to_vec().unwrap())
+        })
+    }
+
+    /// Moves the account to its new places. `old_scores` are the scores before the stats were changed
+    pub(crate) fn internal_update_leaderboards(&mut self, account_id: &AccountId, old_scores: Vec<Option<u128>>, stats: &Stats) {
+        let new_scores = get_scores(stats);
+
+        for ((leaderboard_type, old_score), new_score) in LEADERBOARD_TYPES.iter().zip(old_scores).zip(new_scores) {
+            if old_score == new_score {
+                continue;
+            }
+
+            let mut leaderboard = self.internal_get_leaderboard(*leaderboard_type);
+            if let Some(old_score) = old_score {
+                leaderboard.remove(&(old_score, account_id.clone()));
+            }
+            if let Some(new_score) = new_score {
+                leaderboard.insert(&(new_score, account_id.clone()), &());
+            }
+            self.leaderboards.insert(leaderboard_type, &leaderboard);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// The highest scores first
+    pub fn get_leaderboard(&self, leaderboard_type: LeaderboardType, from_index: u64, limit: u64) -> Vec<(AccountId, U128)> {
+        self.internal_get_leaderboard(leaderboard_type)
+            .iter_rev()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(|((score, account_id), _)| (account_id, U128::from(score)))
+            .collect()
+    }
+
+    /// Adds the accounts that had stats before the leaderboards were introduced
+    pub fn rebuild_leaderboards(&mut self, from_index: u64, limit: u64) {
+        self.assert_owner();
+
+        let account_ids: Vec<AccountId> = self.stats.keys()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect();
+        for account_id in account_ids {
+            let stats = self.internal_get_stats(&account_id);
+            let empty_scores = vec![None; LEADERBOARD_TYPES.len()];
+            self.internal_update_leaderboards(&account_id, empty_scores, &stats);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::manager::VStats;
+    use crate::test_utils::set_context;
+
+    fn set_stats(hockey: &mut Hockey, account_id: &AccountId, games_num: u64, victories_num: u64, rating: u32) {
+        let mut stats = hockey.internal_get_stats(account_id);
+        let old_scores = hockey.internal_get_scores(account_id, &stats);
+        stats.games_num = games_num;
+        stats.victories_num = victories_num;
+        stats.rating = rating;
+
+        hockey.internal_update_leaderboards(account_id, old_scores, &stats);
+        hockey.stats.insert(account_id, &VStats::Current(stats));
+    }
+
+    fn get_account_ids(hockey: &Hockey, leaderboard_type: LeaderboardType) -> Vec<AccountId> {
+        hockey.get_leaderboard(leaderboard_type, 0, 10).into_iter().map(|(account_id, _)| account_id).collect()
+    }
+
+    #[test]
+    fn highest_scores_first() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_stats(&mut hockey, &accounts(1), 3, 1, 1600);
+        set_stats(&mut hockey, &accounts(2), 3, 3, 1400);
+        set_stats(&mut hockey, &accounts(3), 3, 2, 1500);
+
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Rating), vec![accounts(1), accounts(3), accounts(2)]);
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Victories), vec![accounts(2), accounts(3), accounts(1)]);
+        assert_eq!(hockey.get_leaderboard(LeaderboardType::Rating, 1, 1), vec![(accounts(3), U128::from(1500))]);
+        assert!(hockey.get_leaderboard(LeaderboardType::Rating, 3, 1).is_empty());
+    }
+
+    #[test]
+    fn account_is_moved_to_its_new_place() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_stats(&mut hockey, &accounts(1), 1, 1, 1600);
+        set_stats(&mut hockey, &accounts(2), 1, 0, 1500);
+        set_stats(&mut hockey, &accounts(1), 2, 1, 1450);
+
+        assert_eq!(hockey.get_leaderboard(LeaderboardType::Rating, 0, 10), vec![
+            (accounts(2), U128::from(1500)),
+            (accounts(1), U128::from(1450)),
+        ]);
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Victories), vec![accounts(1)]);
+    }
+
+    #[test]
+    fn equal_scores_are_ordered_by_account() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_stats(&mut hockey, &accounts(1), 2, 2, 1500);
+        set_stats(&mut hockey, &accounts(2), 2, 2, 1500);
+
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Victories), vec![accounts(2), accounts(1)]);
+    }
+
+    #[test]
+    fn win_rate_needs_enough_games() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_stats(&mut hockey, &accounts(1), MIN_GAMES_FOR_WIN_RATE - 1, MIN_GAMES_FOR_WIN_RATE - 1, 1500);
+        set_stats(&mut hockey, &accounts(2), MIN_GAMES_FOR_WIN_RATE * 2, MIN_GAMES_FOR_WIN_RATE, 1500);
+
+        assert_eq!(hockey.get_leaderboard(LeaderboardType::WinRate, 0, 10), vec![(accounts(2), U128::from(5_000))]);
+
+        set_stats(&mut hockey, &accounts(1), MIN_GAMES_FOR_WIN_RATE, MIN_GAMES_FOR_WIN_RATE - 1, 1500);
+        assert_eq!(hockey.get_leaderboard(LeaderboardType::WinRate, 0, 10), vec![
+            (accounts(1), U128::from(9_000)),
+            (accounts(2), U128::from(5_000)),
+        ]);
+    }
+
+    #[test]
+    fn friendly_games_are_not_ranked() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        for action in [UpdateStatsAction::AddFriendlyGame, UpdateStatsAction::AddWonFriendlyGame] {
+            hockey.internal_update_stats(&accounts(1), action, None, None);
+        }
+        assert_eq!(hockey.internal_get_stats(&accounts(1)).friendly_victories_num, 1);
+        assert!(get_account_ids(&hockey, LeaderboardType::Rating).is_empty());
+        assert!(get_account_ids(&hockey, LeaderboardType::Victories).is_empty());
+
+        // The first ranked game puts both players on the leaderboards
+        hockey.internal_update_ratings(&accounts(1), &accounts(2));
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Rating), vec![accounts(1), accounts(2)]);
+    }
+
+    #[test]
+    fn rebuild_adds_accounts_with_stats() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let mut stats = Stats::new(&accounts(1));
+        stats.victories_num = 4;
+        hockey.stats.insert(&accounts(1), &VStats::Current(stats));
+        assert!(get_account_ids(&hockey, LeaderboardType::Victories).is_empty());
+
+        hockey.rebuild_leaderboards(0, 10);
+        assert_eq!(hockey.get_leaderboard(LeaderboardType::Victories, 0, 10), vec![(accounts(1), U128::from(4))]);
+        assert_eq!(get_account_ids(&hockey, LeaderboardType::Rating), vec![accounts(1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn rebuild_by_not_owner() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_context(accounts(1));
+        hockey.rebuild_leaderboards(0, 10);
+    }
+}
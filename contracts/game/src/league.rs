@@ -0,0 +1,695 @@
+use crate::*;
+use near_sdk::Timestamp;
+use near_sdk::serde::{Deserialize, Serialize};
+
+pub type LeagueId = u64;
+
+const MIN_LEAGUE_SIZE: usize = 3;
+const MAX_LEAGUE_SIZE: usize = 16;
+
+/// Points of the standings
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaguePoints {
+    pub(crate) win: u8,
+    pub(crate) overtime_loss: u8,
+    pub(crate) shootout_loss: u8,
+}
+
+impl Default for LeaguePoints {
+    fn default() -> Self {
+        LeaguePoints {
+            win: 2,
+            overtime_loss: 1,
+            shootout_loss: 1,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum GameFinish {
+    Regulation,
+    Overtime,
+    Shootout,
+    // The game was not played before the deadline
+    Forfeit,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeagueGameResult {
+    // None if both teams did not check in
+    pub(crate) winner_id: Option<AccountId>,
+    pub(crate) home_goals: u8,
+    pub(crate) away_goals: u8,
+    pub(crate) finish: GameFinish,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScheduledGame {
+    pub(crate) round: u32,
+    pub(crate) home_id: AccountId,
+    pub(crate) away_id: AccountId,
+    pub(crate) checked_in: Vec<AccountId>,
+    pub(crate) game_id: Option<GameId>,
+    pub(crate) result: Option<LeagueGameResult>,
+}
+
+impl ScheduledGame {
+    /// A game that was not started before the deadline is won by the team that checked in.
+    /// A started game gets its result when it is finished, or claimed with `claim_timeout`
+    fn get_result(&self, deadline: Timestamp, time: Timestamp) -> Option<LeagueGameResult> {
+        if self.result.is_some() || self.game_id.is_some() || time < deadline {
+            return self.result.clone();
+        }
+
+        Some(LeagueGameResult {
+            winner_id: self.checked_in.first().cloned(),
+            home_goals: 0,
+            away_goals: 0,
+            finish: GameFinish::Forfeit,
+        })
+    }
+}
+
+/// Position of a game in the schedule
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LeagueGame {
+    pub(crate) league_id: LeagueId,
+    pub(crate) index: usize,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct League {
+    pub(crate) league_id: LeagueId,
+    pub(crate) creator_id: AccountId,
+    pub(crate) account_ids: Vec<AccountId>,
+    pub(crate) start_time: Timestamp,
+    // Length of the window of every round, in seconds
+    pub(crate) match_window: u64,
+    pub(crate) points: LeaguePoints,
+    pub(crate) rules: VMatchRules,
+    pub(crate) schedule: Vec<ScheduledGame>,
+}
+
+impl League {
+    fn get_window(&self, round: u32) -> (Timestamp, Timestamp) {
+        let window = self.match_window * SECOND;
+        let start = self.start_time + round as u64 * window;
+        (start, start + window)
+    }
+
+    fn get_deadline(&self, index: usize) -> Timestamp {
+        self.get_window(self.schedule[index].round).1
+    }
+
+    fn get_scheduled_game(&self, index: usize) -> &ScheduledGame {
+        self.schedule.get(index).expect("Scheduled game not found")
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VLeague {
+    Current(League),
+}
+
+impl From<VLeague> for League {
+    fn from(v_league: VLeague) -> Self {
+        match v_league {
+            VLeague::Current(league) => league,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeagueOutput {
+    league_id: LeagueId,
+    creator_id: AccountId,
+    account_ids: Vec<AccountId>,
+    start_time: Timestamp,
+    match_window: u64,
+    points: LeaguePoints,
+    rules: MatchRules,
+    schedule: Vec<ScheduledGame>,
+}
+
+impl From<League> for LeagueOutput {
+    fn from(league: League) -> Self {
+        LeagueOutput {
+            league_id: league.league_id,
+            creator_id: league.creator_id,
+            account_ids: league.account_ids,
+            start_time: league.start_time,
+            match_window: league.match_window,
+            points: league.points,
+            rules: league.rules.into(),
+            schedule: league.schedule,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Standing {
+    account_id: AccountId,
+    games_played: u32,
+    wins: u32,
+    losses: u32,
+    overtime_losses: u32,
+    shootout_losses: u32,
+    forfeits: u32,
+    goals_for: u32,
+    goals_against: u32,
+    goal_differential: i32,
+    points: u32,
+}
+
+impl Standing {
+    fn new(account_id: AccountId) -> Standing {
+        Standing {
+            account_id,
+            games_played: 0,
+            wins: 0,
+            losses: 0,
+            overtime_losses: 0,
+            shootout_losses: 0,
+            forfeits: 0,
+            goals_for: 0,
+            goals_against: 0,
+            goal_differential: 0,
+            points: 0,
+        }
+    }
+
+    fn add_game(&mut self, result: &LeagueGameResult, goals_for: u8, goals_against: u8, points: &LeaguePoints) {
+        self.games_played += 1;
+        self.goals_for += goals_for as u32;
+        self.goals_against += goals_against as u32;
+        self.goal_differential = self.goals_for as i32 - self.goals_against as i32;
+
+        if result.winner_id.as_ref() == Some(&self.account_id) {
+            self.wins += 1;
+            self.points += points.win as u32;
+            return;
+        }
+
+        match result.finish {
+            GameFinish::Regulation => self.losses += 1,
+            GameFinish::Overtime => {
+                self.overtime_losses += 1;
+                self.points += points.overtime_loss as u32;
+            },
+            GameFinish::Shootout => {
+                self.shootout_losses += 1;
+                self.points += points.shootout_loss as u32;
+            },
+            GameFinish::Forfeit => self.forfeits += 1,
+        }
+    }
+}
+
+/// Every account plays every other account once. An odd number of accounts gives a bye every round
+fn get_round_robin_schedule(account_ids: &[AccountId]) -> Vec<ScheduledGame> {
+    let mut slots: Vec<Option<AccountId>> = account_ids.iter().cloned().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+
+    let number_of_slots = slots.len();
+    let mut schedule = vec![];
+    for round in 0..number_of_slots - 1 {
+        for index in 0..number_of_slots / 2 {
+            if let (Some(home_id), Some(away_id)) = (&slots[index], &slots[number_of_slots - 1 - index]) {
+                // The first slot never moves, so it changes sides every round
+                let (home_id, away_id) = if index == 0 && round % 2 == 1 {
+                    (away_id, home_id)
+                } else {
+                    (home_id, away_id)
+                };
+
+                schedule.push(ScheduledGame {
+                    round: round as u32,
+                    home_id: home_id.clone(),
+                    away_id: away_id.clone(),
+                    checked_in: vec![],
+                    game_id: None,
+                    result: None,
+                });
+            }
+        }
+
+        slots[1..].rotate_right(1);
+    }
+
+    schedule
+}
+
+impl Hockey {
+    pub(crate) fn internal_get_league(&self, league_id: &LeagueId) -> League {
+        self.leagues.get(league_id).expect("League not found").into()
+    }
+
+    pub(crate) fn internal_save_league(&mut self, league: League) {
+        self.leagues.insert(&league.league_id.clone(), &VLeague::Current(league));
+    }
+
+    pub(crate) fn internal_get_league_deadline(&self, league_game: &LeagueGame) -> Timestamp {
+        self.internal_get_league(&league_game.league_id).get_deadline(league_game.index)
+    }
+
+    fn internal_start_league_game(&mut self, league: &mut League, index: usize) {
+        let league_id = league.league_id;
+        let scheduled_game = league.get_scheduled_game(index);
+        let home_id = scheduled_game.home_id.clone();
+        let away_id = scheduled_game.away_id.clone();
+        self.internal_check_if_has_game_started(&home_id);
+        self.internal_check_if_has_game_started(&away_id);
+
+        let home_team = self.league_teams.remove(&(league_id, index, home_id.clone())).expect("Team not found");
+        let away_team = self.league_teams.remove(&(league_id, index, away_id.clone())).expect("Team not found");
+
+        let config = GameConfig {
+            deposit: None,
+            opponent_id: None,
+            rules: Some(league.rules.clone()),
+            waiting_since: None,
+            token_id: None,
+        };
+        let game = self.init_game(away_id, home_id, config, (home_team, away_team));
+
+        self.league_games.insert(&game.game_id, &LeagueGame { league_id, index });
+        league.schedule[index].game_id = Some(game.game_id);
+    }
+
+    /// Called when a league game is over
+    pub(crate) fn internal_record_league_game(&mut self, league_game: LeagueGame, game: &Game, winner_id: AccountId) {
+        let mut league = self.internal_get_league(&league_game.league_id);
+        let scheduled_game = &mut league.schedule[league_game.index];
+
+        let (home, away) = if game.user1.account_id == scheduled_game.home_id {
+            (&game.user1, &game.user2)
+        } else {
+            (&game.user2, &game.user1)
+        };
+        let finish = if game.shootout.is_some() {
+            GameFinish::Shootout
+        } else if game.get_period() > 3 {
+            GameFinish::Overtime
+        } else {
+            GameFinish::Regulation
+        };
+
+        scheduled_game.result = Some(LeagueGameResult {
+            winner_id: Some(winner_id),
+            home_goals: home.team.score,
+            away_goals: away.team.score,
+            finish,
+        });
+
+        self.internal_save_league(league);
+    }
+
+    /// Called when a league game is aborted. Both teams have to check in again
+    pub(crate) fn internal_reset_league_game(&mut self, league_game: LeagueGame) {
+        let mut league = self.internal_get_league(&league_game.league_id);
+        let scheduled_game = &mut league.schedule[league_game.index];
+        scheduled_game.game_id = None;
+        scheduled_game.checked_in.clear();
+
+        self.internal_save_league(league);
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Only the owner can create a league. Rounds are played one after another,
+    /// every round has its own window. Full-length matches and default points if not set
+    pub fn create_league(
+        &mut self,
+        account_ids: Vec<AccountId>,
+        start_time: Timestamp,
+        match_window: u64,
+        points: Option<LeaguePoints>,
+        rules: Option<VMatchRules>
+    ) -> LeagueId {
+        self.assert_owner();
+        self.assert_not_paused();
+        assert!((MIN_LEAGUE_SIZE..=MAX_LEAGUE_SIZE).contains(&account_ids.len()),
+                "A league must have from {} to {} accounts", MIN_LEAGUE_SIZE, MAX_LEAGUE_SIZE);
+        let mut unique_account_ids = account_ids.clone();
+        unique_account_ids.sort();
+        unique_account_ids.dedup();
+        assert_eq!(unique_account_ids.len(), account_ids.len(), "Accounts must be unique");
+        assert!(start_time > env::block_timestamp(), "Start time has already passed");
+
+        let rules = rules.unwrap_or_else(|| VMatchRules::Current(MatchRules::default()));
+        MatchRules::from(rules.clone()).assert_valid();
+        assert!(match_window > 0, "Match window must be positive");
+
+        let league_id = self.next_league_id;
+        self.internal_save_league(League {
+            league_id,
+            creator_id: predecessor_account_id(),
+            schedule: get_round_robin_schedule(&account_ids),
+            account_ids,
+            start_time,
+            match_window,
+            points: points.unwrap_or_default(),
+            rules,
+        });
+        self.next_league_id += 1;
+
+        league_id
+    }
+
+    /// Can be called during the window of the round. The game starts once both teams have checked in
+    pub fn check_in(&mut self, league_id: LeagueId, index: usize) -> Promise {
+        self.assert_not_paused();
+        let account_id = predecessor_account_id();
+
+        let league = self.internal_get_league(&league_id);
+        let scheduled_game = league.get_scheduled_game(index);
+        assert!(scheduled_game.home_id == account_id || scheduled_game.away_id == account_id,
+                "You are not a player of this game");
+        assert!(!scheduled_game.checked_in.contains(&account_id), "Already checked in");
+        self.internal_check_if_has_game_started(&account_id);
+
+        let (start, end) = league.get_window(scheduled_game.round);
+        let time = env::block_timestamp();
+        assert!(time >= start && time < end, "The game is not open for check-in");
+
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
+            .with_static_gas(Gas(100_000_000_000_000))
+            .get_owner_team(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(150_000_000_000_000))
+                    .on_get_league_team(league_id, index, account_id)
+            )
+    }
+
+    #[private]
+    pub fn on_get_league_team(&mut self,
+                              league_id: LeagueId,
+                              index: usize,
+                              account_id: AccountId,
+                              #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> Option<GameId> {
+        if call_result.is_err() {
+            log!("The team is incomplete");
+            return None;
+        }
+
+        let mut league = self.internal_get_league(&league_id);
+        // The deadline could pass while the team was requested
+        if env::block_timestamp() >= league.get_deadline(index) || league.schedule[index].checked_in.contains(&account_id) {
+            log!("Check-in is closed");
+            return None;
+        }
+
+        self.league_teams.insert(&(league_id, index, account_id.clone()), &call_result.unwrap());
+        league.schedule[index].checked_in.push(account_id);
+
+        if league.schedule[index].checked_in.len() == 2 {
+            self.internal_start_league_game(&mut league, index);
+        }
+
+        let game_id = league.schedule[index].game_id;
+        self.internal_save_league(league);
+
+        game_id
+    }
+
+    /// Records the forfeit of a game that was not started before the deadline.
+    /// A started game is ended with `claim_timeout`
+    pub fn close_league_game(&mut self, league_id: LeagueId, index: usize) -> LeagueGameResult {
+        let mut league = self.internal_get_league(&league_id);
+        let scheduled_game = league.get_scheduled_game(index);
+        assert!(scheduled_game.result.is_none(), "The game already has a result");
+        assert!(scheduled_game.game_id.is_none(), "The game has started");
+
+        let result = scheduled_game.get_result(league.get_deadline(index), env::block_timestamp())
+            .expect("The game is still open for check-in");
+        for account_id in scheduled_game.checked_in.iter() {
+            self.league_teams.remove(&(league_id, index, account_id.clone()));
+        }
+
+        league.schedule[index].result = Some(result.clone());
+        self.internal_save_league(league);
+
+        result
+    }
+
+    pub fn get_league(&self, league_id: LeagueId) -> LeagueOutput {
+        self.internal_get_league(&league_id).into()
+    }
+
+    /// Games that were not started before the deadline count as forfeits.
+    /// Sorted by points, goal differential and goals
+    pub fn get_standings(&self, league_id: LeagueId) -> Vec<Standing> {
+        let league = self.internal_get_league(&league_id);
+        let time = env::block_timestamp();
+
+        let mut standings: Vec<Standing> = league.account_ids.iter()
+            .map(|account_id| Standing::new(account_id.clone()))
+            .collect();
+
+        for (index, scheduled_game) in league.schedule.iter().enumerate() {
+            if let Some(result) = scheduled_game.get_result(league.get_deadline(index), time) {
+                for standing in standings.iter_mut() {
+                    if standing.account_id == scheduled_game.home_id {
+                        standing.add_game(&result, result.home_goals, result.away_goals, &league.points);
+                    } else if standing.account_id == scheduled_game.away_id {
+                        standing.add_game(&result, result.away_goals, result.home_goals, &league.points);
+                    }
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| b.points.cmp(&a.points)
+            .then(b.goal_differential.cmp(&a.goal_differential))
+            .then(b.goals_for.cmp(&a.goals_for)));
+
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const START_TIME: Timestamp = 100 * SECOND;
+    const MATCH_WINDOW: u64 = 60;
+
+    fn set_context_at(predecessor_account_id: AccountId, timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor_account_id)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    fn get_account_ids(number: usize) -> Vec<AccountId> {
+        (0..number).map(|index| format!("player{}.near", index).parse().unwrap()).collect()
+    }
+
+    /// `accounts(1)`, `accounts(2)` and `accounts(3)`. The schedule is
+    /// 2 - 3 in the first round, 3 - 1 in the second and 1 - 2 in the third
+    fn get_contract_with_league() -> (Hockey, LeagueId) {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let league_id = hockey.create_league(vec![accounts(1), accounts(2), accounts(3)], START_TIME, MATCH_WINDOW, None, None);
+
+        (hockey, league_id)
+    }
+
+    fn check_in(hockey: &mut Hockey, league_id: LeagueId, index: usize, account_id: AccountId, time: Timestamp) -> Option<GameId> {
+        set_context_at(accounts(0), time);
+        hockey.on_get_league_team(league_id, index, account_id.clone(), Ok(get_team_metadata(account_id.as_str())))
+    }
+
+    fn get_round_start(round: u64) -> Timestamp {
+        START_TIME + round * MATCH_WINDOW * SECOND
+    }
+
+    #[test]
+    fn every_pair_meets_once() {
+        for number in MIN_LEAGUE_SIZE..=MAX_LEAGUE_SIZE {
+            let account_ids = get_account_ids(number);
+            let schedule = get_round_robin_schedule(&account_ids);
+            assert_eq!(schedule.len(), number * (number - 1) / 2);
+
+            let mut pairs: Vec<(AccountId, AccountId)> = schedule.iter()
+                .map(|game| (game.home_id.clone().min(game.away_id.clone()), game.home_id.clone().max(game.away_id.clone())))
+                .collect();
+            pairs.sort();
+            pairs.dedup();
+            assert_eq!(pairs.len(), schedule.len());
+
+            let rounds = number + number % 2 - 1;
+            for round in 0..rounds as u32 {
+                let mut round_account_ids: Vec<AccountId> = schedule.iter()
+                    .filter(|game| game.round == round)
+                    .flat_map(|game| [game.home_id.clone(), game.away_id.clone()])
+                    .collect();
+                let games_in_round = round_account_ids.len() / 2;
+                round_account_ids.sort();
+                round_account_ids.dedup();
+
+                // Nobody plays twice in a round, one account has a bye if the number is odd
+                assert_eq!(round_account_ids.len(), games_in_round * 2);
+                assert_eq!(games_in_round, number / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn first_account_changes_sides() {
+        let account_ids = get_account_ids(4);
+        let schedule = get_round_robin_schedule(&account_ids);
+
+        let sides: Vec<bool> = schedule.iter()
+            .filter(|game| game.home_id == account_ids[0] || game.away_id == account_ids[0])
+            .map(|game| game.home_id == account_ids[0])
+            .collect();
+        assert_eq!(sides, vec![true, false, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn only_owner_creates_league() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_context(accounts(1));
+        hockey.create_league(vec![accounts(1), accounts(2), accounts(3)], START_TIME, MATCH_WINDOW, None, None);
+    }
+
+    #[test]
+    fn standings_count_points_and_forfeits() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        let mut league = hockey.internal_get_league(&league_id);
+        league.schedule[0].result = Some(LeagueGameResult {
+            winner_id: Some(accounts(2)),
+            home_goals: 3,
+            away_goals: 1,
+            finish: GameFinish::Regulation,
+        });
+        league.schedule[1].result = Some(LeagueGameResult {
+            winner_id: Some(accounts(1)),
+            home_goals: 2,
+            away_goals: 3,
+            finish: GameFinish::Overtime,
+        });
+        hockey.internal_save_league(league);
+        check_in(&mut hockey, league_id, 2, accounts(2), get_round_start(2));
+
+        // The third game is open until the end of its round
+        set_context_at(accounts(0), get_round_start(3) - 1);
+        let standings = hockey.get_standings(league_id);
+        assert_eq!(standings.iter().map(|standing| standing.games_played).sum::<u32>(), 4);
+
+        set_context_at(accounts(0), get_round_start(3));
+        let standings = hockey.get_standings(league_id);
+        let table: Vec<(AccountId, u32, u32, u32)> = standings.iter()
+            .map(|standing| (standing.account_id.clone(), standing.points, standing.forfeits, standing.overtime_losses))
+            .collect();
+        assert_eq!(table, vec![
+            (accounts(2), 4, 0, 0),
+            (accounts(1), 2, 1, 0),
+            (accounts(3), 1, 0, 1),
+        ]);
+        assert_eq!((standings[1].goals_for, standings[1].goals_against), (3, 2));
+    }
+
+    #[test]
+    fn game_starts_when_both_teams_check_in() {
+        let (mut hockey, league_id) = get_contract_with_league();
+
+        assert!(check_in(&mut hockey, league_id, 0, accounts(2), get_round_start(0)).is_none());
+        let game_id = check_in(&mut hockey, league_id, 0, accounts(3), get_round_start(0)).expect("Game not started");
+
+        let game = hockey.internal_get_game(&game_id);
+        assert_eq!(game.user1.account_id, accounts(2));
+        assert_eq!(game.reward.balance, 0);
+        assert!(hockey.league_teams.get(&(league_id, 0, accounts(2))).is_none());
+        assert_eq!(hockey.get_league(league_id).schedule[0].game_id, Some(game_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Another game already started")]
+    fn game_does_not_start_while_another_is_played() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        check_in(&mut hockey, league_id, 0, accounts(2), get_round_start(0));
+        check_in(&mut hockey, league_id, 0, accounts(3), get_round_start(0)).expect("Game not started");
+
+        // The third round starts while 2 - 3 is still played
+        check_in(&mut hockey, league_id, 2, accounts(1), get_round_start(2));
+        check_in(&mut hockey, league_id, 2, accounts(2), get_round_start(2));
+    }
+
+    #[test]
+    fn closed_game_is_a_forfeit() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        check_in(&mut hockey, league_id, 0, accounts(3), get_round_start(0));
+        assert!(hockey.league_teams.get(&(league_id, 0, accounts(3))).is_some());
+
+        set_context_at(accounts(4), get_round_start(1));
+        let result = hockey.close_league_game(league_id, 0);
+
+        assert_eq!(result.winner_id, Some(accounts(3)));
+        assert!(result.finish == GameFinish::Forfeit);
+        assert!(hockey.league_teams.get(&(league_id, 0, accounts(3))).is_none());
+        assert!(hockey.get_league(league_id).schedule[0].result.is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "The game is still open for check-in")]
+    fn game_is_not_closed_before_the_deadline() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        set_context_at(accounts(4), get_round_start(1) - 1);
+        hockey.close_league_game(league_id, 0);
+    }
+
+    #[test]
+    fn started_game_is_claimed_at_the_deadline() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        check_in(&mut hockey, league_id, 0, accounts(2), get_round_start(0));
+        let game_id = check_in(&mut hockey, league_id, 0, accounts(3), get_round_start(0)).expect("Game not started");
+
+        let mut game = hockey.internal_get_game(&game_id);
+        game.last_active_account = Some(accounts(2));
+        hockey.internal_save_game(game);
+
+        assert_eq!(hockey.get_inactivity_deadline(game_id), get_round_start(1));
+        set_context_at(accounts(4), get_round_start(1));
+        hockey.claim_timeout(game_id);
+
+        let result = hockey.get_league(league_id).schedule[0].result.clone().expect("No result");
+        assert_eq!(result.winner_id, Some(accounts(2)));
+        assert!(hockey.available_games.get(&game_id).is_none());
+    }
+
+    #[test]
+    fn unplayed_started_game_is_a_forfeit_of_both_teams() {
+        let (mut hockey, league_id) = get_contract_with_league();
+        check_in(&mut hockey, league_id, 0, accounts(2), get_round_start(0));
+        let game_id = check_in(&mut hockey, league_id, 0, accounts(3), get_round_start(0)).expect("Game not started");
+
+        set_context_at(accounts(4), get_round_start(1));
+        hockey.claim_timeout(game_id);
+        let result = hockey.close_league_game(league_id, 0);
+
+        assert!(result.winner_id.is_none());
+        assert!(hockey.available_games.get(&game_id).is_none());
+    }
+}
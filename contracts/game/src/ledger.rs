@@ -0,0 +1,115 @@
+use crate::*;
+use near_sdk::json_types::U128;
+
+impl Hockey {
+    /// Adds to the balance the account can claim. NEAR if `token_id` is not set
+    pub(crate) fn internal_credit(&mut self, account_id: &AccountId, token_id: &Option<AccountId>, amount: Balance) {
+        if amount == 0 {
+            return;
+        }
+
+        let mut balances = self.claimable.get(account_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::ClaimablePerAccount { account_id: hash_account_id(account_id.as_str()) }.try_to_vec().unwrap())
+        });
+        let balance = balances.get(token_id).unwrap_or(0);
+        balances.insert(token_id, &(balance + amount));
+        self.claimable.insert(account_id, &balances);
+    }
+
+    fn internal_debit(&mut self, account_id: &AccountId, token_id: &Option<AccountId>, amount: Balance) {
+        let mut balances = self.claimable.get(account_id).expect("Nothing to claim");
+        let balance = balances.get(token_id).unwrap_or(0);
+        assert!(amount <= balance, "Not enough balance. Available: {}", balance);
+
+        if amount == balance {
+            balances.remove(token_id);
+        } else {
+            balances.insert(token_id, &(balance - amount));
+        }
+        self.claimable.insert(account_id, &balances);
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Sends the claimed amount to the caller. NEAR if `token_id` is not set
+    pub fn claim(&mut self, token_id: Option<AccountId>, amount: U128) -> Promise {
+        let account_id = predecessor_account_id();
+        assert!(amount.0 > 0, "Amount must be positive");
+
+        self.internal_debit(&account_id, &token_id, amount.0);
+        self.internal_transfer(&token_id, &account_id, amount.0)
+    }
+
+    /// Failed transfers are credited back, so the receiver can claim them later
+    #[private]
+    pub fn on_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: Option<AccountId>,
+        amount: U128,
+        #[callback_result] call_result: Result<(), PromiseError>
+    ) -> bool {
+        if call_result.is_err() {
+            let token = token_id.as_ref().map(|token_id| token_id.as_str()).unwrap_or(NEAR);
+            log!("Transfer of {} {} to {} failed", amount.0, token, receiver_id);
+            self.internal_credit(&receiver_id, &token_id, amount.0);
+            return false;
+        }
+
+        true
+    }
+
+    /// Balances by token. NEAR has no token id
+    pub fn get_claimable(&self, account_id: AccountId) -> Vec<(Option<AccountId>, U128)> {
+        self.claimable.get(&account_id)
+            .map(|balances| balances.iter()
+                .map(|(token_id, balance)| (token_id, U128::from(balance)))
+                .collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::set_context;
+
+    #[test]
+    fn claim_debits_the_ledger() {
+        set_context(accounts(1));
+        let mut hockey = Hockey::new(accounts(0));
+        let token_id = Some(accounts(3));
+
+        hockey.internal_credit(&accounts(1), &None, 100);
+        hockey.internal_credit(&accounts(1), &token_id, 50);
+        hockey.internal_credit(&accounts(1), &None, 20);
+
+        hockey.claim(None, U128::from(70));
+        hockey.claim(token_id.clone(), U128::from(50));
+
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(50))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough balance")]
+    fn claim_more_than_balance() {
+        set_context(accounts(1));
+        let mut hockey = Hockey::new(accounts(0));
+
+        hockey.internal_credit(&accounts(1), &None, 100);
+        hockey.claim(None, U128::from(101));
+    }
+
+    #[test]
+    fn failed_transfer_is_credited_back() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+
+        assert!(!hockey.on_transfer(accounts(1), None, U128::from(100), Err(PromiseError::Failed)));
+        assert!(hockey.on_transfer(accounts(1), None, U128::from(100), Ok(())));
+
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(100))]);
+    }
+}
@@ -1,28 +1,64 @@
 extern crate core;
 
-use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::collections::{LookupMap, LookupSet, TreeMap, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::{CryptoHash, ext_contract, Gas, Promise, PromiseError};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{AccountId, Balance, BorshStorageKey, env, serde_json, log, near_bindgen, PanicOnDefault};
 use near_sdk::env::{predecessor_account_id};
 use game::actions::action::ActionData::{CoachSpeech, GoalieBack, GoalieOut, TakeTO};
 
+use crate::career::{TopScorersKey, VPlayerCareerStats};
+use crate::event_log::GameEvent;
 use crate::external::{ext_manage_team};
 use crate::manager::{GameConfig, TokenBalance, UpdateStatsAction, VGameConfig, VStats};
+use crate::owner::ContractConfig;
+use crate::migration::STATE_VERSION;
 use team::players::player::PlayerPosition;
 use team::players::field_player::FieldPlayer;
 use crate::game::actions::action::{ActionData, ActionTypes};
-use crate::game::game::{Event, Game, GameState};
+use crate::game::autopilot::AutopilotPolicy;
+use crate::game::game::{Event, Game, GameState, GameV1, VGame};
+use crate::game::match_rules::{MatchRules, VMatchRules};
+use crate::game::rng::{ChainRng, Rng};
 use crate::team::team_metadata::TeamMetadata;
-use crate::user_info::{Account, hash_account_id, UserInfo};
+use crate::tournament::{BracketGame, TournamentId, VTournament};
+use crate::league::{LeagueGame, LeagueId, VLeague};
+use crate::history::{HeadToHead, VGameResult};
+use crate::leaderboard::{Leaderboard, LeaderboardType};
+use crate::referral::AffiliateEarnings;
+use crate::bot::BotDifficulty;
+use crate::user_info::{Account, hash_account_id, UserId, UserInfo, VAccount};
 
 mod game;
 mod user_info;
 mod manager;
 mod team;
 mod external;
+mod event_log;
+mod career;
+mod forfeit;
+mod spectator;
+mod rating;
+mod ft;
+mod owner;
+mod migration;
+mod tournament;
+mod league;
+mod history;
+mod leaderboard;
+mod ledger;
+mod referral;
+mod friendly;
+mod bot;
+#[cfg(test)]
+mod test_utils;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod simulator;
 
+// Default, can be changed by the owner
 const NFT_CONTRACT: &str = "hcm.parh.testnet";
+// Token id of NEAR rewards
+const NEAR: &str = "NEAR";
 
 type GameId = u64;
 type SRC = String;
@@ -31,7 +67,7 @@ pub type TokenId = String;
 // 1 second in nanoseconds
 const SECOND: u64 = 1000000000;
 
-// 1 NEAR
+// 1 NEAR by default
 const MIN_DEPOSIT: Balance = 1_000_000_000_000_000_000_000_000;
 const ONE_YOCTO: Balance = 1;
 
@@ -54,36 +90,153 @@ enum StorageKey {
     SentFriendPlay{ account_id: CryptoHash},
     FriendRequestsReceived { account_id: CryptoHash},
     RequestsPlayReceived { account_id: CryptoHash},
+    GameEvents,
+    GameEventsPerGame { game_id: GameId },
+    PlayRequestRules,
+    PlayerCareers,
+    FinishedGames,
+    FtServiceFees,
+    PlayRequestTokens,
+    VersionedGames,
+    VersionedAccounts,
+    Tournaments,
+    TournamentTeams,
+    TournamentGames,
+    Leagues,
+    LeagueTeams,
+    LeagueGames,
+    MatchHistory,
+    MatchHistoryPerAccount { account_id: CryptoHash },
+    HeadToHead,
+    Leaderboards,
+    Leaderboard { leaderboard_type: LeaderboardType },
+    Claimable,
+    ClaimablePerAccount { account_id: CryptoHash },
+    AffiliateEarnings,
+    AffiliateEarningsPerReferrer { account_id: CryptoHash },
+    FriendlyGames,
+    PracticeQueue,
+    PracticeTeams,
+    BotGames,
+    BotTeams,
+    AutopilotPolicies,
+    TopScorers,
+    ActiveGames,
 }
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 struct Hockey {
-    games: LookupMap<GameId, Game>,
+    state_version: u16,
+    owner_id: AccountId,
+    contract_config: ContractConfig,
+    // New games cannot be started while paused
+    is_paused: bool,
+
+    games: LookupMap<GameId, VGame>,
+    // Games stored before versioning
+    legacy_games: LookupMap<GameId, GameV1>,
+    game_events: LookupMap<GameId, Vector<GameEvent>>,
     teams: LookupMap<AccountId, TeamMetadata>,
     available_players: UnorderedMap<Balance, UnorderedMap<AccountId, VGameConfig>>,
     stats: UnorderedMap<AccountId, VStats>,
     available_games: UnorderedMap<GameId, (AccountId, AccountId)>,
-
-    accounts: UnorderedMap<AccountId, Account>,
+    // Running game of each player of `available_games`
+    active_games: LookupMap<AccountId, GameId>,
+
+    accounts: UnorderedMap<AccountId, VAccount>,
+    // Accounts stored before versioning
+    legacy_accounts: UnorderedMap<AccountId, Account>,
+    // (sender, receiver) -> rules of a play request
+    play_request_rules: LookupMap<(AccountId, AccountId), VMatchRules>,
+    player_careers: UnorderedMap<TokenId, VPlayerCareerStats>,
+    // Players with points, sorted by points and goals
+    top_scorers: TreeMap<TopScorersKey, ()>,
+    finished_games: Vector<GameId>,
+    whitelisted_tokens: UnorderedSet<AccountId>,
+    // (sender, receiver) -> token of a play request, if it is not NEAR
+    play_request_tokens: LookupMap<(AccountId, AccountId), AccountId>,
+    ft_service_fees: LookupMap<AccountId, Balance>,
+    tournaments: UnorderedMap<TournamentId, VTournament>,
+    // Teams of the registered players, taken at registration
+    tournament_teams: LookupMap<(TournamentId, AccountId), TeamMetadata>,
+    tournament_games: LookupMap<GameId, BracketGame>,
+    leagues: UnorderedMap<LeagueId, VLeague>,
+    // Teams of the checked in players of scheduled games
+    league_teams: LookupMap<(LeagueId, usize, AccountId), TeamMetadata>,
+    league_games: LookupMap<GameId, LeagueGame>,
+    match_history: LookupMap<AccountId, Vector<VGameResult>>,
+    // (account, opponent) -> record of the account against the opponent
+    head_to_head: LookupMap<(AccountId, AccountId), HeadToHead>,
+    leaderboards: LookupMap<LeaderboardType, Leaderboard>,
+    // Rewards and refunds by token. NEAR has no token id
+    claimable: LookupMap<AccountId, UnorderedMap<Option<AccountId>, Balance>>,
+    // referrer -> affiliates of all levels and what they produced
+    affiliate_earnings: LookupMap<AccountId, UnorderedMap<AccountId, AffiliateEarnings>>,
+    friendly_games: LookupSet<GameId>,
+    // Players waiting for a game without a stake and their teams
+    practice_queue: UnorderedMap<AccountId, VGameConfig>,
+    practice_teams: LookupMap<AccountId, TeamMetadata>,
+    bot_games: LookupMap<GameId, BotDifficulty>,
+    // Rosters set by the owner. Players are generated for the other difficulties
+    bot_teams: LookupMap<BotDifficulty, TeamMetadata>,
+    // Copied into the games of the manager when they are created
+    autopilot_policies: LookupMap<AccountId, AutopilotPolicy>,
 
     next_game_id: GameId,
+    next_tournament_id: TournamentId,
+    next_league_id: LeagueId,
     service_fee: Balance,
 }
 
 #[near_bindgen]
 impl Hockey {
     #[init]
-    pub fn new() -> Self {
+    pub fn new(owner_id: AccountId) -> Self {
         Self {
-            games: LookupMap::new(StorageKey::Games),
+            state_version: STATE_VERSION,
+            owner_id,
+            contract_config: ContractConfig::default(),
+            is_paused: false,
+
+            games: LookupMap::new(StorageKey::VersionedGames),
+            legacy_games: LookupMap::new(StorageKey::Games),
+            game_events: LookupMap::new(StorageKey::GameEvents),
             teams: LookupMap::new(StorageKey::Teams),
             available_players: UnorderedMap::new(StorageKey::Deposit),
             stats: UnorderedMap::new(StorageKey::Stats),
             available_games: UnorderedMap::new(StorageKey::AvailableGames),
-
-            accounts: UnorderedMap::new(StorageKey::Account),
+            active_games: LookupMap::new(StorageKey::ActiveGames),
+
+            accounts: UnorderedMap::new(StorageKey::VersionedAccounts),
+            legacy_accounts: UnorderedMap::new(StorageKey::Account),
+            play_request_rules: LookupMap::new(StorageKey::PlayRequestRules),
+            player_careers: UnorderedMap::new(StorageKey::PlayerCareers),
+            top_scorers: TreeMap::new(StorageKey::TopScorers),
+            finished_games: Vector::new(StorageKey::FinishedGames),
+            whitelisted_tokens: UnorderedSet::new(StorageKey::WhitelistedTokens),
+            play_request_tokens: LookupMap::new(StorageKey::PlayRequestTokens),
+            ft_service_fees: LookupMap::new(StorageKey::FtServiceFees),
+            tournaments: UnorderedMap::new(StorageKey::Tournaments),
+            tournament_teams: LookupMap::new(StorageKey::TournamentTeams),
+            tournament_games: LookupMap::new(StorageKey::TournamentGames),
+            leagues: UnorderedMap::new(StorageKey::Leagues),
+            league_teams: LookupMap::new(StorageKey::LeagueTeams),
+            league_games: LookupMap::new(StorageKey::LeagueGames),
+            match_history: LookupMap::new(StorageKey::MatchHistory),
+            head_to_head: LookupMap::new(StorageKey::HeadToHead),
+            leaderboards: LookupMap::new(StorageKey::Leaderboards),
+            claimable: LookupMap::new(StorageKey::Claimable),
+            affiliate_earnings: LookupMap::new(StorageKey::AffiliateEarnings),
+            friendly_games: LookupSet::new(StorageKey::FriendlyGames),
+            practice_queue: UnorderedMap::new(StorageKey::PracticeQueue),
+            practice_teams: LookupMap::new(StorageKey::PracticeTeams),
+            bot_games: LookupMap::new(StorageKey::BotGames),
+            bot_teams: LookupMap::new(StorageKey::BotTeams),
+            autopilot_policies: LookupMap::new(StorageKey::AutopilotPolicies),
             next_game_id: 0,
+            next_tournament_id: 0,
+            next_league_id: 0,
             service_fee: 0,
         }
     }
@@ -92,44 +245,41 @@ impl Hockey {
 
 #[near_bindgen]
 impl Hockey {
+    pub(crate) fn internal_has_game_started(&self, account_id: &AccountId) -> bool {
+        self.active_games.contains_key(account_id)
+    }
+
     pub(crate) fn internal_check_if_has_game_started(&self, account_id: &AccountId) {
-        let games_already_started: Vec<(AccountId, AccountId)> = self.available_games.values_as_vector()
-            .iter()
-            .filter(|(player_1, player_2)| *player_1 == *account_id || *player_2 == *account_id)
-            .collect();
-        assert_eq!(games_already_started.len(), 0, "Another game already started");
+        assert!(!self.internal_has_game_started(account_id), "Another game already started");
     }
 
-    pub(crate) fn internal_add_referral(&mut self,
-                                        account_id: &AccountId,
-                                        referrer_id: &Option<AccountId>
-    ) {
-        if self.stats.get(account_id).is_none() && self.is_account_exists(referrer_id) {
-            if let Some(referrer_id_unwrapped) = referrer_id.clone() {
-                self.internal_update_stats(account_id,
-                                           UpdateStatsAction::AddReferral,
-                                           (*referrer_id).clone(),
-                                           None);
-                self.internal_update_stats(&referrer_id_unwrapped,
-                                           UpdateStatsAction::AddAffiliate,
-                                           Some(account_id.clone()),
-                                           None);
-                log!("Referrer {} added for {}", referrer_id_unwrapped, account_id);
-            }
-        }
+    fn internal_is_waiting(&self, account_id: &AccountId, deposit: Balance) -> bool {
+        self.available_players.get(&deposit)
+            .map(|available_players_by_deposit| available_players_by_deposit.get(account_id).is_some())
+            .unwrap_or(false)
     }
 
     #[payable]
     pub fn make_available(&mut self, config: GameConfig) -> Promise {
-        let account_id: &AccountId = &predecessor_account_id();
+        let account_id = predecessor_account_id();
         let deposit: Balance = env::attached_deposit();
-        assert!(deposit >= MIN_DEPOSIT,
+        let min_deposit = self.contract_config.min_deposit;
+        assert!(deposit >= min_deposit,
                 "Deposit is too small. Attached: {}, Required: {}",
                 deposit,
-                MIN_DEPOSIT
+                min_deposit
         );
 
-        ext_manage_team::ext(AccountId::new_unchecked(NFT_CONTRACT.parse().unwrap()))
+        self.internal_make_available(account_id, deposit, GameConfig { token_id: None, ..config })
+    }
+
+    pub(crate) fn internal_make_available(&mut self, account_id: AccountId, deposit: Balance, config: GameConfig) -> Promise {
+        self.assert_not_paused();
+        config.get_rules().assert_valid();
+        assert!(!self.internal_is_waiting(&account_id, deposit), "Already in the waiting list");
+        self.internal_check_if_has_game_started(&account_id);
+
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
             .with_static_gas(Gas(100_000_000_000_000))
             .get_owner_team(account_id.clone())
             .then(
@@ -147,101 +297,90 @@ impl Hockey {
                    #[callback_result] call_result: Result<TeamMetadata, PromiseError>
     ) -> Option<Game> {
         if call_result.is_err() {
-            Promise::new(account_id).transfer(deposit);
+            self.internal_credit(&account_id, &config.token_id, deposit);
             log!("The team is incomplete");
             return None;
         }
 
+        // The player could join the waiting list or start a game while the team was requested
+        if self.internal_is_waiting(&account_id, deposit) || self.internal_has_game_started(&account_id) {
+            self.internal_credit(&account_id, &config.token_id, deposit);
+            log!("Already in the waiting list or in a game");
+            return None;
+        }
+
         let team = call_result.unwrap();
 
         let mut available_players_by_deposit = self.available_players.get(&deposit).unwrap_or_else(|| {
             UnorderedMap::new(StorageKey::AvailablePlayers {deposit: hash_account_id(&serde_json::to_string(&deposit).expect(""))}.try_to_vec().unwrap())
         });
 
-        return if available_players_by_deposit.len() == 0 {
-            available_players_by_deposit.insert(&account_id, &VGameConfig::Current(GameConfig {
-                deposit: Some(deposit),
-                opponent_id: config.opponent_id
-            }));
-
-            self.internal_check_if_has_game_started(&account_id);
-            self.available_players.insert(&deposit, &available_players_by_deposit);
-            self.teams.insert(&account_id, &team);
-            None
-        } else {
-            assert!(available_players_by_deposit.get(&account_id).is_none(), "Already in the waiting list the list");
-            let available_players = self.get_available_players(0, 1, &available_players_by_deposit);
-            self.internal_check_if_has_game_started(&account_id);
-
-            let opponent_id = available_players.get(0).expect("Cannot find opponent id");
-            self.teams.insert(&account_id, &team);
-
-            Some(self.start_game(opponent_id.0.clone(), deposit, account_id))
-        }
-    }
-
-    pub fn start_game(&mut self, opponent_id: AccountId, deposit: Balance, account_id: AccountId) -> Game {
-        if let Some(opponent_config) = self.available_players.get(&deposit).expect("Deposit not found").get(&opponent_id) {
-            let config: GameConfig = opponent_config.into();
-            assert_eq!(deposit, config.deposit.unwrap_or(0), "Wrong deposit");
-
-            assert_ne!(account_id.clone(), opponent_id.clone(), "Find a friend to play");
-
-            self.internal_check_if_has_game_started(&account_id);
+        let opponent_id = self.internal_find_opponent(&account_id, &config, &available_players_by_deposit);
+
+        return match opponent_id {
+            None => {
+                available_players_by_deposit.insert(&account_id, &VGameConfig::Current(GameConfig {
+                    deposit: Some(deposit),
+                    opponent_id: config.opponent_id,
+                    rules: config.rules,
+                    waiting_since: Some(env::block_timestamp()),
+                    token_id: config.token_id,
+                }));
+
+                self.available_players.insert(&deposit, &available_players_by_deposit);
+                self.teams.insert(&account_id, &team);
+                None
+            },
+            Some(opponent_id) => {
+                self.teams.insert(&account_id, &team);
 
-            if let Some(ref player_id) = config.opponent_id {
-                assert_eq!(*player_id, account_id, "Wrong account");
+                Some(self.start_game(opponent_id, deposit, account_id))
             }
-
-            let team = self.teams.remove(&account_id).expect("Team not found");
-            let opponent_team = self.teams.remove(&opponent_id).expect("Team not found");
-
-            self.init_game(opponent_id, account_id.clone(), config.clone(),  (team, opponent_team))
-        } else {
-            panic!("Your opponent is not ready");
         }
     }
 
-    pub fn init_game(
+    /// Stores a new game between two accounts. Teams are in the order of the accounts
+    pub(crate) fn internal_create_game(
         &mut self,
-        opponent_id: AccountId,
         account_id: AccountId,
-        config: GameConfig,
-        teams: (TeamMetadata, TeamMetadata)
+        opponent_id: AccountId,
+        reward: TokenBalance,
+        rules: MatchRules,
+        teams: (TeamMetadata, TeamMetadata),
+        is_friendly: bool
     ) -> Game {
-        let reward = TokenBalance {
-            token_id: Some("NEAR".into()),
-            balance: config.deposit.unwrap_or(0) * 2,
-        };
-
         let game_id = self.next_game_id;
 
-        let game = Game::new(teams, account_id.clone(),
-                             opponent_id.clone(),
-                             reward, &game_id);
+        let mut game = Game::new(teams, account_id.clone(),
+                                 opponent_id.clone(),
+                                 reward, &game_id,
+                                 Rng::Chain(ChainRng::default()),
+                                 rules);
+        game.user1.autopilot = self.autopilot_policies.get(&account_id);
+        game.user2.autopilot = self.autopilot_policies.get(&opponent_id);
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game.clone());
 
-        self.available_games.insert(&game_id, &(account_id.clone(), opponent_id.clone()));
+        self.internal_add_available_game(game_id, &account_id, &opponent_id);
 
         self.next_game_id += 1;
 
-        let mut available_players_by_deposit = self.available_players.get(&config.deposit.expect("Incorrect game config")).expect("Deposit not found");
-        available_players_by_deposit.remove(&opponent_id);
-        available_players_by_deposit.remove(&account_id);
-
-        self.available_players.insert(&config.deposit.unwrap(), &available_players_by_deposit);
-
-        self.internal_update_stats(&account_id, UpdateStatsAction::AddPlayedGame, None, None);
-        self.internal_update_stats(&opponent_id, UpdateStatsAction::AddPlayedGame, None, None);
+        let action = if is_friendly {
+            self.friendly_games.insert(&game_id);
+            UpdateStatsAction::AddFriendlyGame
+        } else {
+            UpdateStatsAction::AddPlayedGame
+        };
+        self.internal_update_stats(&account_id, action, None, None);
+        self.internal_update_stats(&opponent_id, action, None, None);
 
         game
     }
 
     pub fn generate_event(&mut self, game_id: GameId) -> Event {
-        let game: &mut Game = &mut self.internal_get_game(&game_id);
+        let mut game = self.internal_get_game(&game_id);
 
-        assert!(game.winner_index.is_none(), "Game already finished");
+        game.assert_not_finished();
 
         let time = env::block_timestamp();
         let d_time = time - game.last_event_generation_time;
@@ -255,7 +394,7 @@ impl Hockey {
         if d_time == 0 {
             game.number_of_generated_events_in_current_block += 1;
             if game.number_of_generated_events_in_current_block == game.max_number_of_generated_events_in_block {
-                game.event_generation_delay = SECOND;
+                game.event_generation_delay = game.rules.block_limit_delay * SECOND;
             }
         } else {
             game.event_generation_delay = 0;
@@ -263,7 +402,16 @@ impl Hockey {
         }
 
         game.last_event_generation_time = time;
+        game.record_activity(&predecessor_account_id());
 
+        let generated_event = self.internal_generate_event(&mut game);
+
+        self.internal_save_game(game);
+
+        generated_event
+    }
+
+    pub(crate) fn internal_generate_event(&mut self, game: &mut Game) -> Event {
         let mut generated_actions = game.step();
 
         let game_state = game.get_game_state();
@@ -273,39 +421,72 @@ impl Hockey {
 
         match game_state.0 {
             GameState::GameOver { winner_id: winner_index} => {
-                let winner_account = if game.user1.user_id == winner_index {
-                    game.user1.account_id.clone()
-                } else {
-                    game.user2.account_id.clone()
-                };
+                generated_actions.push(self.internal_finish_game(game, winner_index));
+            },
+            _ => {}
+        };
 
-                let reward = self.internal_distribute_reward(
-                    &game.reward, &winner_account, game_id);
+        let generated_event = game.generate_event(&mut generated_actions);
+        self.internal_save_event(game.game_id, &generated_event);
 
-                let winner_account = game.get_user_info(winner_index);
-                generated_actions.push(ActionData::GameFinished {
-                    action_type: ActionTypes::GameFinished,
-                    winner_account_id: winner_account.account_id.clone(),
-                    reward
-                });
+        generated_event
+    }
 
-                game.winner_index = Some(winner_index);
+    /// Pays out the reward to the winner and stops the game
+    pub(crate) fn internal_finish_game(&mut self, game: &mut Game, winner_index: UserId) -> ActionData {
+        let winner_account = game.get_user_info(winner_index).account_id.clone();
+        let loser_account = game.get_opponent_info(winner_index).account_id.clone();
 
-                self.internal_stop_game(game_id);
-            },
-            _ => {}
+        if self.friendly_games.remove(&game.game_id) {
+            return self.internal_finish_friendly_game(game, winner_index);
+        }
+        if self.bot_games.remove(&game.game_id).is_some() {
+            return self.internal_finish_bot_game(game, winner_index);
+        }
+
+        let bracket_game = self.tournament_games.remove(&game.game_id);
+        let league_game = self.league_games.remove(&game.game_id);
+        // Tournaments and leagues have no stakes in their games, ratings still change
+        let reward = if bracket_game.is_some() || league_game.is_some() {
+            self.internal_update_stats(&winner_account, UpdateStatsAction::AddWonGame, None, None);
+            0
+        } else {
+            self.internal_distribute_reward(&game.reward, &winner_account, game.game_id)
         };
 
-        let generated_event = game.generate_event(&mut generated_actions);
+        game.winner_index = Some(winner_index);
 
-        self.games.insert(&game_id, &game);
+        self.internal_update_ratings(&winner_account, &loser_account);
+        self.internal_update_player_careers(game);
+        self.internal_record_game_results(game, winner_index, reward);
+        self.internal_stop_game(game.game_id);
+        self.finished_games.push(&game.game_id);
 
-        generated_event
+        if let Some(bracket_game) = bracket_game {
+            self.internal_advance_tournament(bracket_game, winner_account.clone());
+        }
+        if let Some(league_game) = league_game {
+            self.internal_record_league_game(league_game, game, winner_account.clone());
+        }
+
+        ActionData::GameFinished {
+            action_type: ActionTypes::GameFinished,
+            winner_account_id: winner_account,
+            reward,
+            box_score: game.box_score.clone(),
+        }
     }
 
     // TODO make private on release
     pub fn internal_stop_game(&mut self, game_id: GameId) {
-        self.available_games.remove(&game_id);
+        if let Some((player_1, player_2)) = self.available_games.remove(&game_id) {
+            for account_id in [player_1, player_2] {
+                // The contract account plays all the bot games at once
+                if self.active_games.get(&account_id) == Some(game_id) {
+                    self.active_games.remove(&account_id);
+                }
+            }
+        }
         log!{"{}", game_id};
     }
 
@@ -314,6 +495,129 @@ impl Hockey {
     }
 }
 
+// Games are started only by the contract: from the waiting list, play requests, tournaments and leagues
+impl Hockey {
+    pub(crate) fn internal_add_available_game(&mut self, game_id: GameId, account_id: &AccountId, opponent_id: &AccountId) {
+        self.available_games.insert(&game_id, &(account_id.clone(), opponent_id.clone()));
+        self.active_games.insert(account_id, &game_id);
+        self.active_games.insert(opponent_id, &game_id);
+    }
+
+    pub(crate) fn start_game(&mut self, opponent_id: AccountId, deposit: Balance, account_id: AccountId) -> Game {
+        if let Some(opponent_config) = self.available_players.get(&deposit).expect("Deposit not found").get(&opponent_id) {
+            let config: GameConfig = opponent_config.into();
+            assert_eq!(deposit, config.deposit.unwrap_or(0), "Wrong deposit");
+
+            assert_ne!(account_id.clone(), opponent_id.clone(), "Find a friend to play");
+
+            self.internal_check_if_has_game_started(&account_id);
+
+            if let Some(ref player_id) = config.opponent_id {
+                assert_eq!(*player_id, account_id, "Wrong account");
+            }
+
+            let team = self.teams.remove(&account_id).expect("Team not found");
+            let opponent_team = self.teams.remove(&opponent_id).expect("Team not found");
+
+            self.init_game(opponent_id, account_id.clone(), config.clone(),  (team, opponent_team))
+        } else {
+            panic!("Your opponent is not ready");
+        }
+    }
+
+    pub(crate) fn init_game(
+        &mut self,
+        opponent_id: AccountId,
+        account_id: AccountId,
+        config: GameConfig,
+        teams: (TeamMetadata, TeamMetadata)
+    ) -> Game {
+        let reward = TokenBalance {
+            token_id: Some(config.token_id.clone().map(|token_id| token_id.to_string()).unwrap_or_else(|| NEAR.into())),
+            balance: config.deposit.unwrap_or(0) * 2,
+        };
+
+        // Friendly games have a zero stake. Tournament and league games have no stake at all,
+        // but change ratings and leaderboards like any other game
+        let is_friendly = config.deposit == Some(0);
+        let game = self.internal_create_game(account_id.clone(), opponent_id.clone(), reward, config.get_rules(), teams, is_friendly);
+
+        // Players of friend and scheduled games are not always in the waiting list
+        if let Some(mut available_players_by_deposit) = config.deposit.and_then(|deposit| self.available_players.get(&deposit)) {
+            available_players_by_deposit.remove(&opponent_id);
+            available_players_by_deposit.remove(&account_id);
+
+            self.available_players.insert(&config.deposit.unwrap(), &available_players_by_deposit);
+        }
+
+        game
+    }
+}
+
 
 #[cfg(test)]
-mod tests {}
\ No newline at end of file
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    // An exported method of the same name is an inherent method of `HockeyExt`,
+    // it would be called instead and the test would not compile
+    trait NotExported {
+        fn start_game(&self) -> bool { true }
+        fn init_game(&self) -> bool { true }
+    }
+
+    impl NotExported for HockeyExt {}
+
+    fn get_config() -> GameConfig {
+        GameConfig {
+            deposit: None,
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        }
+    }
+
+    #[test]
+    fn waiting_player_gets_the_second_deposit_back() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+
+        assert!(hockey.on_get_team(accounts(1), 10, get_config(), Ok(get_team_metadata("a"))).is_none());
+        assert!(hockey.on_get_team(accounts(1), 10, get_config(), Ok(get_team_metadata("a"))).is_none());
+
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(10))]);
+        assert_eq!(hockey.available_players.get(&10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn player_in_a_game_gets_the_deposit_back() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.on_get_team(accounts(1), 10, get_config(), Ok(get_team_metadata("a")));
+        assert!(hockey.on_get_team(accounts(2), 10, get_config(), Ok(get_team_metadata("b"))).is_some());
+
+        assert!(hockey.on_get_team(accounts(2), 20, get_config(), Ok(get_team_metadata("b"))).is_none());
+        assert_eq!(hockey.get_claimable(accounts(2)), vec![(None, U128::from(20))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already in the waiting list")]
+    fn waiting_player_cannot_stake_again() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.on_get_team(accounts(1), 10, get_config(), Ok(get_team_metadata("a")));
+
+        hockey.internal_make_available(accounts(1), 10, get_config());
+    }
+
+    #[test]
+    fn games_are_not_started_from_outside() {
+        let contract = Hockey::ext(accounts(0));
+        assert!(contract.start_game());
+        assert!(contract.init_game());
+    }
+}
@@ -1,10 +1,13 @@
-use near_sdk::{AccountId, Balance, log, Promise, PromiseOrValue};
+use near_sdk::{AccountId, Balance, log, PromiseOrValue, Timestamp};
 use near_sdk::collections::{UnorderedMap, UnorderedSet};
 use near_sdk::json_types::U128;
 use crate::{Game, GameId, Hockey, StorageKey};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use crate::*;
+use crate::game::match_rules::{MatchRules, VMatchRules};
+use crate::rating::INITIAL_RATING;
+use crate::game::game::VGame;
 
 
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -15,22 +18,89 @@ pub struct TokenBalance {
     pub(crate) balance: Balance,
 }
 
+impl TokenBalance {
+    /// None for NEAR
+    pub fn get_ft_token_id(&self) -> Option<AccountId> {
+        match &self.token_id {
+            Some(token_id) if token_id != NEAR => Some(AccountId::new_unchecked(token_id.clone())),
+            _ => None,
+        }
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct GameConfig {
     pub(crate) deposit: Option<Balance>,
     pub(crate) opponent_id: Option<AccountId>,
+    // Full-length match if not set
+    pub(crate) rules: Option<VMatchRules>,
+    // Set when the account is added to the waiting list
+    pub(crate) waiting_since: Option<Timestamp>,
+    // Contract of the staked fungible token. NEAR if not set
+    pub(crate) token_id: Option<AccountId>,
+}
+
+impl GameConfig {
+    pub fn get_rules(&self) -> MatchRules {
+        self.rules.clone().map(|rules| rules.into()).unwrap_or_default()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GameConfigV1 {
+    deposit: Option<Balance>,
+    opponent_id: Option<AccountId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GameConfigV2 {
+    deposit: Option<Balance>,
+    opponent_id: Option<AccountId>,
+    rules: Option<VMatchRules>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GameConfigV3 {
+    deposit: Option<Balance>,
+    opponent_id: Option<AccountId>,
+    rules: Option<VMatchRules>,
+    waiting_since: Option<Timestamp>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VGameConfig {
-    Current(GameConfig)
+    V1(GameConfigV1),
+    V2(GameConfigV2),
+    V3(GameConfigV3),
+    Current(GameConfig),
 }
 
 impl From<VGameConfig> for GameConfig {
     fn from(v_game_config: VGameConfig) -> Self {
         match v_game_config {
+            VGameConfig::V1(game_config) => GameConfig {
+                deposit: game_config.deposit,
+                opponent_id: game_config.opponent_id,
+                rules: None,
+                waiting_since: None,
+                token_id: None,
+            },
+            VGameConfig::V2(game_config) => GameConfig {
+                deposit: game_config.deposit,
+                opponent_id: game_config.opponent_id,
+                rules: game_config.rules,
+                waiting_since: None,
+                token_id: None,
+            },
+            VGameConfig::V3(game_config) => GameConfig {
+                deposit: game_config.deposit,
+                opponent_id: game_config.opponent_id,
+                rules: game_config.rules,
+                waiting_since: game_config.waiting_since,
+                token_id: None,
+            },
             VGameConfig::Current(game_config) => game_config,
         }
     }
@@ -41,18 +111,22 @@ impl From<VGameConfig> for GameConfig {
 pub struct GameConfigOutput {
     deposit: U128,
     opponent_id: Option<AccountId>,
+    rules: MatchRules,
+    token_id: Option<AccountId>,
 }
 
 impl From<GameConfig> for GameConfigOutput {
     fn from(config: GameConfig) -> Self {
         GameConfigOutput {
             deposit: U128::from(config.deposit.unwrap_or(0)),
+            rules: config.get_rules(),
             opponent_id: config.opponent_id,
+            token_id: config.token_id,
         }
     }
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum UpdateStatsAction {
     AddPlayedGame,
     AddReferral,
@@ -61,17 +135,23 @@ pub enum UpdateStatsAction {
     AddTotalReward,
     AddAffiliateReward,
     AddPenaltyGame,
+    AddFriendlyGame,
+    AddWonFriendlyGame,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct Stats {
-    referrer_id: Option<AccountId>,
+    pub(crate) referrer_id: Option<AccountId>,
     affiliates: UnorderedSet<AccountId>,
-    games_num: u64,
-    victories_num: u64,
-    penalties_num: u64,
-    total_reward: UnorderedMap<Option<AccountId>, Balance>,
-    total_affiliate_reward: UnorderedMap<Option<AccountId>, Balance>,
+    pub(crate) games_num: u64,
+    pub(crate) victories_num: u64,
+    pub(crate) penalties_num: u64,
+    pub(crate) total_reward: UnorderedMap<Option<AccountId>, Balance>,
+    pub(crate) total_affiliate_reward: UnorderedMap<Option<AccountId>, Balance>,
+    pub(crate) rating: u32,
+    // Games without a stake. Not counted in the rating and leaderboards
+    pub(crate) friendly_games_num: u64,
+    pub(crate) friendly_victories_num: u64,
 }
 
 impl Stats {
@@ -84,18 +164,70 @@ impl Stats {
             penalties_num: 0,
             total_reward: UnorderedMap::new(StorageKey::TotalRewards { account_id: account_id.clone() }),
             total_affiliate_reward: UnorderedMap::new(StorageKey::TotalAffiliateRewards { account_id: account_id.clone() }),
+            rating: INITIAL_RATING,
+            friendly_games_num: 0,
+            friendly_victories_num: 0,
         }
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StatsV1 {
+    referrer_id: Option<AccountId>,
+    affiliates: UnorderedSet<AccountId>,
+    games_num: u64,
+    victories_num: u64,
+    penalties_num: u64,
+    total_reward: UnorderedMap<Option<AccountId>, Balance>,
+    total_affiliate_reward: UnorderedMap<Option<AccountId>, Balance>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StatsV2 {
+    referrer_id: Option<AccountId>,
+    affiliates: UnorderedSet<AccountId>,
+    games_num: u64,
+    victories_num: u64,
+    penalties_num: u64,
+    total_reward: UnorderedMap<Option<AccountId>, Balance>,
+    total_affiliate_reward: UnorderedMap<Option<AccountId>, Balance>,
+    rating: u32,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VStats {
+    V1(StatsV1),
+    V2(StatsV2),
     Current(Stats),
 }
 
 impl From<VStats> for Stats {
     fn from(v_stats: VStats) -> Self {
         match v_stats {
+            VStats::V1(stats) => Stats {
+                referrer_id: stats.referrer_id,
+                affiliates: stats.affiliates,
+                games_num: stats.games_num,
+                victories_num: stats.victories_num,
+                penalties_num: stats.penalties_num,
+                total_reward: stats.total_reward,
+                total_affiliate_reward: stats.total_affiliate_reward,
+                rating: INITIAL_RATING,
+                friendly_games_num: 0,
+                friendly_victories_num: 0,
+            },
+            VStats::V2(stats) => Stats {
+                referrer_id: stats.referrer_id,
+                affiliates: stats.affiliates,
+                games_num: stats.games_num,
+                victories_num: stats.victories_num,
+                penalties_num: stats.penalties_num,
+                total_reward: stats.total_reward,
+                total_affiliate_reward: stats.total_affiliate_reward,
+                rating: stats.rating,
+                friendly_games_num: 0,
+                friendly_victories_num: 0,
+            },
             VStats::Current(stats) => stats,
         }
     }
@@ -110,7 +242,13 @@ pub struct StatsOutput {
     victories_num: u64,
     penalties_num: u64,
     total_reward: U128,
-    total_affiliate_reward: U128
+    total_affiliate_reward: U128,
+    // Rewards in fungible tokens
+    ft_total_reward: Vec<(AccountId, U128)>,
+    ft_total_affiliate_reward: Vec<(AccountId, U128)>,
+    rating: u32,
+    friendly_games_num: u64,
+    friendly_victories_num: u64,
 }
 
 impl From<Stats> for StatsOutput {
@@ -121,13 +259,23 @@ impl From<Stats> for StatsOutput {
             games_num: stats.games_num,
             victories_num: stats.victories_num,
             penalties_num: stats.penalties_num,
-            // TODO Add FT
             total_reward: U128::from(stats.total_reward.get(&None).unwrap_or(0)),
             total_affiliate_reward: U128::from(stats.total_affiliate_reward.get(&None).unwrap_or(0)),
+            ft_total_reward: get_ft_rewards(&stats.total_reward),
+            ft_total_affiliate_reward: get_ft_rewards(&stats.total_affiliate_reward),
+            rating: stats.rating,
+            friendly_games_num: stats.friendly_games_num,
+            friendly_victories_num: stats.friendly_victories_num,
         }
     }
 }
 
+fn get_ft_rewards(rewards: &UnorderedMap<Option<AccountId>, Balance>) -> Vec<(AccountId, U128)> {
+    rewards.iter()
+        .filter_map(|(token_id, reward)| token_id.map(|token_id| (token_id, U128::from(reward))))
+        .collect()
+}
+
 impl Hockey {
     pub(crate) fn internal_distribute_reward(
         &mut self,
@@ -135,11 +283,11 @@ impl Hockey {
         winner_id: &AccountId,
         game_id: GameId
     ) -> Balance {
-        // TODO add for FT
+        let ft_token_id = token_balance.get_ft_token_id();
         let amount = token_balance.balance;
-        let fee = amount / 10;
+        let fee = self.contract_config.get_fee(amount);
         let winner_reward: Balance = amount - fee;
-        Promise::new(winner_id.clone()).transfer(winner_reward);
+        self.internal_credit(winner_id, &ft_token_id, winner_reward);
 
         let reward = match serde_json::to_string(&(game_id, (winner_id.clone(), winner_reward.clone()))) {
             Ok(res) => res,
@@ -147,21 +295,18 @@ impl Hockey {
         };
         log!("{}", reward);
 
-        let stats = self.internal_get_stats(winner_id);
-        let referrer_fee = if let Some(referrer_id) = stats.referrer_id {
-            let referrer_fee = fee / 2;
-            log!("Affiliate reward for {} is {}", referrer_id, referrer_fee);
-            self.internal_update_stats(&referrer_id, UpdateStatsAction::AddAffiliateReward, None, Some(referrer_fee));
-            Promise::new(referrer_id.clone()).transfer(referrer_fee);
-            referrer_fee
-        } else {
-            0
-        };
+        let referrer_fee = self.internal_pay_referrers(winner_id, &ft_token_id, fee);
 
-        self.service_fee += fee - referrer_fee;
+        match ft_token_id {
+            Some(ref token_id) => {
+                let ft_service_fee = self.ft_service_fees.get(token_id).unwrap_or(0);
+                self.ft_service_fees.insert(token_id, &(ft_service_fee + fee - referrer_fee));
+            },
+            None => self.service_fee += fee - referrer_fee,
+        }
 
         self.internal_update_stats(winner_id, UpdateStatsAction::AddWonGame, None   , None);
-        self.internal_update_stats(winner_id, UpdateStatsAction::AddTotalReward, None, Some(winner_reward));
+        self.internal_update_stats(winner_id, UpdateStatsAction::AddTotalReward, ft_token_id, Some(winner_reward));
 
         // finish
         // TODO add to stats
@@ -174,6 +319,7 @@ impl Hockey {
                                         additional_account_id: Option<AccountId>,
                                         balance: Option<Balance>) {
         let mut stats = self.internal_get_stats(account_id);
+        let old_scores = self.internal_get_scores(account_id, &stats);
 
         if action == UpdateStatsAction::AddPlayedGame {
             stats.games_num += 1
@@ -189,20 +335,27 @@ impl Hockey {
             stats.victories_num += 1;
         } else if action == UpdateStatsAction::AddTotalReward {
             if let Some(balance_unwrapped) = balance {
-                // TODO Add FT
-                let total_reward = stats.total_reward.get(&None).unwrap_or(0);
-                stats.total_reward.insert(&None, &(total_reward + balance_unwrapped));
+                // additional_account_id is the token of the reward
+                let total_reward = stats.total_reward.get(&additional_account_id).unwrap_or(0);
+                stats.total_reward.insert(&additional_account_id, &(total_reward + balance_unwrapped));
             }
         } else if action == UpdateStatsAction::AddAffiliateReward {
             if let Some(balance_unwrapped) = balance {
-                // TODO Add FT
-                let total_affiliate_reward = stats.total_affiliate_reward.get(&None).unwrap_or(0);
-                stats.total_affiliate_reward.insert(&None, &(total_affiliate_reward + balance_unwrapped));
+                let total_affiliate_reward = stats.total_affiliate_reward.get(&additional_account_id).unwrap_or(0);
+                stats.total_affiliate_reward.insert(&additional_account_id, &(total_affiliate_reward + balance_unwrapped));
             }
         } else if action == UpdateStatsAction::AddPenaltyGame {
             stats.penalties_num += 1;
+        } else if action == UpdateStatsAction::AddFriendlyGame {
+            stats.friendly_games_num += 1;
+        } else if action == UpdateStatsAction::AddWonFriendlyGame {
+            stats.friendly_victories_num += 1;
         }
 
+        // Friendly games are not shown in the leaderboards
+        if action != UpdateStatsAction::AddFriendlyGame && action != UpdateStatsAction::AddWonFriendlyGame {
+            self.internal_update_leaderboards(account_id, old_scores, &stats);
+        }
         self.stats.insert(account_id, &VStats::Current(stats));
     }
 
@@ -214,8 +367,16 @@ impl Hockey {
         }
     }
 
+    /// Games stored before versioning are read from the legacy map
     pub(crate) fn internal_get_game(&self, game_id: &GameId) -> Game {
-        self.games.get(game_id).expect("Game not found")
+        self.games.get(game_id)
+            .map(|game| game.into())
+            .or_else(|| self.legacy_games.get(game_id).map(|game| game.into()))
+            .expect("Game not found")
+    }
+
+    pub(crate) fn internal_save_game(&mut self, game: Game) {
+        self.games.insert(&game.game_id.clone(), &VGame::Current(game));
     }
 
     pub(crate) fn is_account_exists(&self, account_id: &Option<AccountId>) -> bool {
@@ -238,7 +399,8 @@ impl Hockey {
             let config: GameConfig = v_game_config.into();
             available_players_by_deposit.remove(&account_id);
             self.available_players.insert(&deposit, &available_players_by_deposit);
-            PromiseOrValue::Promise(Promise::new(account_id).transfer(config.deposit.unwrap_or(0)))
+            self.internal_credit(&account_id, &config.token_id, config.deposit.unwrap_or(0));
+            PromiseOrValue::Value(true)
         } else {
             PromiseOrValue::Value(false)
         }
@@ -274,6 +436,10 @@ impl Hockey {
         U128::from(self.service_fee)
     }
 
+    pub fn get_ft_service_fee(&self, token_id: AccountId) -> U128 {
+        U128::from(self.ft_service_fees.get(&token_id).unwrap_or(0))
+    }
+
     pub fn is_already_in_the_waiting_list(&self, account_id: AccountId, deposit: Balance) -> bool {
         let available_players_by_deposit = self.available_players.get(&deposit).expect("Deposit not found");
         !available_players_by_deposit.get(&account_id).is_none()
@@ -0,0 +1,261 @@
+use crate::*;
+
+// The first version was deployed without a version. `migrate` upgrades it to this layout,
+// so a field added to `Hockey` needs a new version and a new migration
+pub const STATE_VERSION: u16 = 2;
+
+/// Layout of `Hockey` in the first version. Games and accounts were stored raw
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct HockeyV1 {
+    pub(crate) games: LookupMap<GameId, GameV1>,
+    pub(crate) teams: LookupMap<AccountId, TeamMetadata>,
+    pub(crate) available_players: UnorderedMap<Balance, UnorderedMap<AccountId, VGameConfig>>,
+    pub(crate) stats: UnorderedMap<AccountId, VStats>,
+    pub(crate) available_games: UnorderedMap<GameId, (AccountId, AccountId)>,
+
+    pub(crate) accounts: UnorderedMap<AccountId, Account>,
+
+    pub(crate) next_game_id: GameId,
+    pub(crate) service_fee: Balance,
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Upgrades the state of the first version. Raw games and accounts stay in their maps
+    /// and are moved to the versioned ones the next time they are saved
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(owner_id: AccountId) -> Self {
+        let old_state: HockeyV1 = env::state_read().expect("Failed to read the old state");
+
+        let mut active_games = LookupMap::new(StorageKey::ActiveGames);
+        for (game_id, (player_1, player_2)) in old_state.available_games.iter() {
+            active_games.insert(&player_1, &game_id);
+            active_games.insert(&player_2, &game_id);
+        }
+
+        Self {
+            state_version: STATE_VERSION,
+            owner_id,
+            contract_config: ContractConfig::default(),
+            is_paused: false,
+
+            games: LookupMap::new(StorageKey::VersionedGames),
+            legacy_games: old_state.games,
+            game_events: LookupMap::new(StorageKey::GameEvents),
+            teams: old_state.teams,
+            available_players: old_state.available_players,
+            stats: old_state.stats,
+            available_games: old_state.available_games,
+            active_games,
+
+            accounts: UnorderedMap::new(StorageKey::VersionedAccounts),
+            legacy_accounts: old_state.accounts,
+            play_request_rules: LookupMap::new(StorageKey::PlayRequestRules),
+            player_careers: UnorderedMap::new(StorageKey::PlayerCareers),
+            top_scorers: TreeMap::new(StorageKey::TopScorers),
+            finished_games: Vector::new(StorageKey::FinishedGames),
+            whitelisted_tokens: UnorderedSet::new(StorageKey::WhitelistedTokens),
+            play_request_tokens: LookupMap::new(StorageKey::PlayRequestTokens),
+            ft_service_fees: LookupMap::new(StorageKey::FtServiceFees),
+            tournaments: UnorderedMap::new(StorageKey::Tournaments),
+            tournament_teams: LookupMap::new(StorageKey::TournamentTeams),
+            tournament_games: LookupMap::new(StorageKey::TournamentGames),
+            leagues: UnorderedMap::new(StorageKey::Leagues),
+            league_teams: LookupMap::new(StorageKey::LeagueTeams),
+            league_games: LookupMap::new(StorageKey::LeagueGames),
+            match_history: LookupMap::new(StorageKey::MatchHistory),
+            head_to_head: LookupMap::new(StorageKey::HeadToHead),
+            leaderboards: LookupMap::new(StorageKey::Leaderboards),
+            claimable: LookupMap::new(StorageKey::Claimable),
+            affiliate_earnings: LookupMap::new(StorageKey::AffiliateEarnings),
+            friendly_games: LookupSet::new(StorageKey::FriendlyGames),
+            practice_queue: UnorderedMap::new(StorageKey::PracticeQueue),
+            practice_teams: LookupMap::new(StorageKey::PracticeTeams),
+            bot_games: LookupMap::new(StorageKey::BotGames),
+            bot_teams: LookupMap::new(StorageKey::BotTeams),
+            autopilot_policies: LookupMap::new(StorageKey::AutopilotPolicies),
+
+            next_game_id: old_state.next_game_id,
+            next_tournament_id: 0,
+            next_league_id: 0,
+            service_fee: old_state.service_fee,
+        }
+    }
+
+    pub fn get_state_version(&self) -> u16 {
+        self.state_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::game::actions::action::ActionDataV1;
+    use crate::game::actions::shot_type::{ShotType, ShotZone};
+    use crate::user_info::{UserInfoV1, VAccount};
+    use crate::test_utils::{get_game, set_context};
+
+    fn get_user_info_v1(user: UserInfo) -> UserInfoV1 {
+        UserInfoV1 {
+            user_id: user.user_id,
+            team: user.team,
+            account_id: user.account_id,
+            take_to_called: user.take_to_called,
+            coach_speech_called: user.coach_speech_called,
+            is_goalie_out: user.is_goalie_out,
+        }
+    }
+
+    /// A game of the first version, after a shot of the first user
+    fn get_game_v1(game_id: GameId) -> GameV1 {
+        let game = get_game(game_id);
+        GameV1 {
+            game_id: game.game_id,
+            user1: get_user_info_v1(game.user1),
+            user2: get_user_info_v1(game.user2),
+            reward: game.reward,
+            winner_index: None,
+            player_with_puck: Some((1, "a-0".to_string())),
+            zone_number: 3,
+            turns: 10,
+            last_action: ActionDataV1(ActionData::Shot {
+                action_type: ActionTypes::Shot,
+                account_id: accounts(1),
+                player_number: 0,
+                player_position: PlayerPosition::Center,
+                shot_type: ShotType::Slap,
+                shot_zone: ShotZone::GloveSideHigh,
+            }),
+            last_event_generation_time: 0,
+            event_generation_delay: 0,
+            max_number_of_generated_events_in_block: 2,
+            number_of_generated_events_in_current_block: 1,
+        }
+    }
+
+    fn get_account(account_id: &AccountId) -> Account {
+        let prefix = account_id.as_str();
+        Account {
+            user_id: account_id.clone(),
+            friends: UnorderedSet::new(format!("{}f", prefix).into_bytes()),
+            sent_friend_requests: UnorderedSet::new(format!("{}s", prefix).into_bytes()),
+            friend_requests_received: UnorderedSet::new(format!("{}r", prefix).into_bytes()),
+            sent_requests_play: UnorderedMap::new(format!("{}sp", prefix).into_bytes()),
+            requests_play_received: UnorderedMap::new(format!("{}rp", prefix).into_bytes()),
+        }
+    }
+
+    fn get_old_state() -> HockeyV1 {
+        HockeyV1 {
+            games: LookupMap::new(StorageKey::Games),
+            teams: LookupMap::new(StorageKey::Teams),
+            available_players: UnorderedMap::new(StorageKey::Deposit),
+            stats: UnorderedMap::new(StorageKey::Stats),
+            available_games: UnorderedMap::new(StorageKey::AvailableGames),
+
+            accounts: UnorderedMap::new(StorageKey::Account),
+
+            next_game_id: 3,
+            service_fee: 100,
+        }
+    }
+
+    #[test]
+    fn migrate_keeps_old_state() {
+        set_context(accounts(0));
+        let mut old_state = get_old_state();
+        old_state.available_games.insert(&2, &(accounts(1), accounts(2)));
+        env::state_write(&old_state);
+
+        let hockey = Hockey::migrate(accounts(4));
+
+        assert_eq!(hockey.get_state_version(), STATE_VERSION);
+        assert_eq!(hockey.owner_id, accounts(4));
+        assert!(!hockey.is_paused);
+        assert_eq!(hockey.next_game_id, 3);
+        assert_eq!(hockey.service_fee, 100);
+        assert_eq!(hockey.contract_config.fee_bps, ContractConfig::default().fee_bps);
+        assert_eq!(hockey.available_games.get(&2), Some((accounts(1), accounts(2))));
+        assert_eq!(hockey.get_active_game(accounts(2)), Some(2));
+    }
+
+    #[test]
+    fn raw_game_is_upgraded() {
+        set_context(accounts(0));
+        let mut old_state = get_old_state();
+        old_state.games.insert(&1, &get_game_v1(1));
+        env::state_write(&old_state);
+
+        let mut hockey = Hockey::migrate(accounts(0));
+        assert!(hockey.games.get(&1).is_none());
+
+        let game = hockey.internal_get_game(&1);
+        assert_eq!(game.game_id, 1);
+        assert_eq!(game.turns, 10);
+        assert_eq!(game.user1.account_id, accounts(1));
+        assert_eq!(game.user2.account_id, accounts(2));
+        assert_eq!(game.rules, MatchRules::default());
+        match game.last_action {
+            ActionData::Shot { shot_type, shot_zone, .. } => {
+                assert_eq!((shot_type, shot_zone), (ShotType::Wrist, ShotZone::FiveHole));
+            },
+            _ => panic!("Last action is lost"),
+        }
+
+        hockey.internal_save_game(game);
+        match hockey.games.get(&1) {
+            Some(VGame::Current(game)) => assert_eq!(game.game_id, 1),
+            None => panic!("Game is not versioned"),
+        }
+    }
+
+    #[test]
+    fn actions_of_the_first_version_are_read() {
+        // Save { action_type: Save, account_id: "bob", goalie_number: 30 } and one more byte
+        let mut bytes = vec![11, 11, 3, 0, 0, 0];
+        bytes.extend_from_slice(b"bob");
+        bytes.extend_from_slice(&[30, 99]);
+
+        let mut buf = &bytes[..];
+        match <ActionDataV1 as BorshDeserialize>::deserialize(&mut buf).unwrap().0 {
+            ActionData::Save { account_id, goalie_number, .. } => {
+                assert_eq!((account_id, goalie_number), (accounts(1), 30));
+            },
+            _ => panic!("Save expected"),
+        }
+        assert_eq!(buf, &[99]);
+
+        // StartPeriod { action_type: StartPeriod, number: 2 } has not changed
+        let bytes = [1, 1, 2, 99];
+        let mut buf = &bytes[..];
+        match <ActionDataV1 as BorshDeserialize>::deserialize(&mut buf).unwrap().0 {
+            ActionData::StartPeriod { number, .. } => assert_eq!(number, 2),
+            _ => panic!("StartPeriod expected"),
+        }
+        assert_eq!(buf, &[99]);
+    }
+
+    #[test]
+    fn raw_account_is_upgraded() {
+        set_context(accounts(0));
+        let account_id = accounts(2);
+        let mut old_state = get_old_state();
+        old_state.accounts.insert(&account_id, &get_account(&account_id));
+        env::state_write(&old_state);
+
+        let mut hockey = Hockey::migrate(accounts(0));
+
+        let mut account = hockey.internal_get_account(&account_id).expect("Account not found");
+        assert_eq!(account.user_id, account_id);
+
+        account.friends.insert(&accounts(3));
+        hockey.internal_save_account(&account_id, account);
+
+        match hockey.accounts.get(&account_id) {
+            Some(VAccount::Current(account)) => assert!(account.friends.contains(&accounts(3))),
+            None => panic!("Account is not versioned"),
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::referral::MAX_REFERRAL_LEVELS;
+
+// 100% in basis points
+pub(crate) const MAX_BPS: u16 = 10_000;
+
+// 10%
+const DEFAULT_FEE_BPS: u16 = 1_000;
+// 40% of the fee to the direct referrer, 10% to its referrer
+const DEFAULT_REFERRAL_TIERS: [u16; 2] = [4_000, 1_000];
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractConfig {
+    pub(crate) nft_contract_id: AccountId,
+    // Share of the reward kept by the contract
+    pub(crate) fee_bps: u16,
+    // Shares of the fee paid to the referrers of the winner, the direct one first
+    pub(crate) referral_tiers: Vec<u16>,
+    pub(crate) min_deposit: Balance,
+}
+
+impl Default for ContractConfig {
+    fn default() -> Self {
+        ContractConfig {
+            nft_contract_id: AccountId::new_unchecked(NFT_CONTRACT.into()),
+            fee_bps: DEFAULT_FEE_BPS,
+            referral_tiers: DEFAULT_REFERRAL_TIERS.to_vec(),
+            min_deposit: MIN_DEPOSIT,
+        }
+    }
+}
+
+impl ContractConfig {
+    pub fn get_fee(&self, amount: Balance) -> Balance {
+        amount * self.fee_bps as Balance / MAX_BPS as Balance
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractConfigOutput {
+    owner_id: AccountId,
+    nft_contract_id: AccountId,
+    fee_bps: u16,
+    referral_tiers: Vec<u16>,
+    min_deposit: U128,
+}
+
+impl Hockey {
+    pub(crate) fn assert_owner(&self) {
+        assert_eq!(predecessor_account_id(), self.owner_id, "Only the owner can call this method");
+    }
+
+    pub(crate) fn assert_not_paused(&self) {
+        assert!(!self.is_paused, "The contract is paused");
+    }
+
+    /// Refunds the stakes of both players and stops the game
+    pub(crate) fn internal_abort_game(&mut self, mut game: Game) -> Event {
+        let game_id = game.game_id;
+        let token_id = game.reward.get_ft_token_id();
+        let stake = game.reward.balance / 2;
+        self.internal_credit(&game.user1.account_id, &token_id, stake);
+        self.internal_credit(&game.user2.account_id, &token_id, stake);
+
+        game.is_aborted = true;
+        self.internal_stop_game(game_id);
+
+        // A bracket game is played again
+        if let Some(bracket_game) = self.tournament_games.remove(&game_id) {
+            self.internal_replay_bracket_game(bracket_game);
+        }
+        if let Some(league_game) = self.league_games.remove(&game_id) {
+            self.internal_reset_league_game(league_game);
+        }
+        self.friendly_games.remove(&game_id);
+        self.bot_games.remove(&game_id);
+
+        game.last_event_generation_time = env::block_timestamp();
+        let generated_event = game.generate_event(&mut vec![ActionData::GameAborted {
+            action_type: ActionTypes::GameAborted,
+        }]);
+
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &generated_event);
+
+        generated_event
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn set_nft_contract(&mut self, nft_contract_id: AccountId) {
+        self.assert_owner();
+        self.contract_config.nft_contract_id = nft_contract_id;
+    }
+
+    pub fn set_fee(&mut self, fee_bps: u16) {
+        self.assert_owner();
+        assert!(fee_bps <= MAX_BPS, "Fee cannot be more than {} basis points", MAX_BPS);
+        self.contract_config.fee_bps = fee_bps;
+    }
+
+    /// Shares of the fee in basis points, the direct referrer first
+    pub fn set_referral_tiers(&mut self, referral_tiers: Vec<u16>) {
+        self.assert_owner();
+        assert!(referral_tiers.len() <= MAX_REFERRAL_LEVELS, "No more than {} referral levels", MAX_REFERRAL_LEVELS);
+        let total: u32 = referral_tiers.iter().map(|share| *share as u32).sum();
+        assert!(total <= MAX_BPS as u32, "Referral tiers cannot be more than {} basis points", MAX_BPS);
+        self.contract_config.referral_tiers = referral_tiers;
+    }
+
+    pub fn set_min_deposit(&mut self, min_deposit: U128) {
+        self.assert_owner();
+        self.contract_config.min_deposit = min_deposit.0;
+    }
+
+    pub fn withdraw_service_fee(&mut self, amount: U128, receiver_id: AccountId) -> Promise {
+        self.assert_owner();
+        assert!(amount.0 <= self.service_fee, "Not enough service fee. Available: {}", self.service_fee);
+
+        self.service_fee -= amount.0;
+        self.internal_transfer(&None, &receiver_id, amount.0)
+    }
+
+    pub fn withdraw_ft_service_fee(&mut self, token_id: AccountId, amount: U128, receiver_id: AccountId) -> Promise {
+        self.assert_owner();
+        let ft_service_fee = self.ft_service_fees.get(&token_id).unwrap_or(0);
+        assert!(amount.0 <= ft_service_fee, "Not enough service fee. Available: {}", ft_service_fee);
+
+        self.ft_service_fees.insert(&token_id, &(ft_service_fee - amount.0));
+        self.internal_transfer(&Some(token_id), &receiver_id, amount.0)
+    }
+
+    /// Blocks new games. Running games can still be played to the end
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        self.is_paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        self.is_paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Stops the game and refunds the stakes of both players. No one wins
+    pub fn abort_game(&mut self, game_id: GameId) -> Event {
+        self.assert_owner();
+
+        let game = self.internal_get_game(&game_id);
+        game.assert_not_finished();
+
+        self.internal_abort_game(game)
+    }
+
+    pub fn get_config(&self) -> ContractConfigOutput {
+        ContractConfigOutput {
+            owner_id: self.owner_id.clone(),
+            nft_contract_id: self.contract_config.nft_contract_id.clone(),
+            fee_bps: self.contract_config.fee_bps,
+            referral_tiers: self.contract_config.referral_tiers.clone(),
+            min_deposit: U128::from(self.contract_config.min_deposit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const DEPOSIT: Balance = 10;
+
+    /// `accounts(0)` is the owner
+    fn get_contract() -> Hockey {
+        set_context(accounts(0));
+        Hockey::new(accounts(0))
+    }
+
+    fn get_config(deposit: Option<Balance>) -> GameConfig {
+        GameConfig {
+            deposit,
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        }
+    }
+
+    /// `accounts(1)` against `accounts(2)`, both staking `DEPOSIT`
+    fn get_contract_with_game() -> (Hockey, GameId) {
+        let mut hockey = get_contract();
+        let game = hockey.init_game(accounts(2), accounts(1), get_config(Some(DEPOSIT)),
+                                    (get_team_metadata("a"), get_team_metadata("b")));
+        (hockey, game.game_id)
+    }
+
+    #[test]
+    fn owner_changes_the_config() {
+        let mut hockey = get_contract();
+
+        hockey.set_nft_contract(accounts(4));
+        hockey.set_fee(500);
+        hockey.set_min_deposit(U128::from(7));
+
+        let config = &hockey.contract_config;
+        assert_eq!(config.nft_contract_id, accounts(4));
+        assert_eq!(config.min_deposit, 7);
+        assert_eq!(config.get_fee(1_000), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn config_changed_by_other_account() {
+        let mut hockey = get_contract();
+
+        set_context(accounts(1));
+        hockey.set_fee(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee cannot be more than 10000 basis points")]
+    fn fee_over_the_reward() {
+        let mut hockey = get_contract();
+        hockey.set_fee(MAX_BPS + 1);
+    }
+
+    #[test]
+    fn owner_withdraws_service_fees() {
+        let mut hockey = get_contract();
+        hockey.service_fee = 100;
+        hockey.ft_service_fees.insert(&accounts(3), &50);
+
+        hockey.withdraw_service_fee(U128::from(60), accounts(1));
+        hockey.withdraw_ft_service_fee(accounts(3), U128::from(50), accounts(1));
+
+        assert_eq!(hockey.service_fee, 40);
+        assert_eq!(hockey.ft_service_fees.get(&accounts(3)), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough service fee. Available: 100")]
+    fn withdraw_more_than_service_fee() {
+        let mut hockey = get_contract();
+        hockey.service_fee = 100;
+
+        hockey.withdraw_service_fee(U128::from(101), accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is paused")]
+    fn no_waiting_list_while_paused() {
+        let mut hockey = get_contract();
+        hockey.pause();
+
+        hockey.internal_make_available(accounts(1), DEPOSIT, get_config(None));
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is paused")]
+    fn no_requests_to_play_while_paused() {
+        let mut hockey = get_contract();
+        hockey.pause();
+
+        hockey.internal_send_request_play(accounts(1), accounts(2), None, DEPOSIT, None);
+    }
+
+    #[test]
+    fn running_game_goes_on_while_paused() {
+        let (mut hockey, game_id) = get_contract_with_game();
+        hockey.pause();
+        assert!(hockey.is_paused());
+
+        let event = hockey.generate_event(game_id);
+        hockey.unpause();
+
+        assert!(!hockey.is_paused());
+        assert!(!event.actions.is_empty());
+        assert_eq!(hockey.get_game_events(game_id, 0, 10).len(), 1);
+    }
+
+    #[test]
+    fn aborted_game_refunds_both_stakes() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        hockey.abort_game(game_id);
+
+        let game = hockey.internal_get_game(&game_id);
+        assert!(game.is_aborted);
+        assert!(hockey.available_games.get(&game_id).is_none());
+        for account_id in [accounts(1), accounts(2)] {
+            assert_eq!(hockey.get_claimable(account_id.clone()), vec![(None, U128::from(DEPOSIT))]);
+            assert_eq!(hockey.internal_get_stats(&account_id).victories_num, 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Game already finished")]
+    fn aborted_game_is_not_played() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        hockey.abort_game(game_id);
+        hockey.generate_event(game_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn game_aborted_by_player() {
+        let (mut hockey, game_id) = get_contract_with_game();
+
+        set_context(accounts(1));
+        hockey.abort_game(game_id);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::*;
+use near_sdk::Timestamp;
+use crate::manager::{GameConfig, VStats};
+use crate::leaderboard::LeaderboardType;
+
+pub const INITIAL_RATING: u32 = 1500;
+const K_FACTOR: f64 = 32.0;
+
+// The rating difference accepted for a player who has just started waiting
+const RATING_WINDOW: u32 = 100;
+// How much the window widens for every minute of waiting
+const RATING_WINDOW_GROWTH: u32 = 50;
+const MINUTE: u64 = 60 * SECOND;
+// Waiting players checked for a match, so a long waiting list does not run out of gas
+const MAX_CHECKED_OPPONENTS: usize = 50;
+
+/// Elo ratings of the winner and the loser after the game
+fn get_new_ratings(winner_rating: u32, loser_rating: u32) -> (u32, u32) {
+    let expected_win = 1.0 / (1.0 + 10f64.powf((loser_rating as f64 - winner_rating as f64) / 400.0));
+    let change = (K_FACTOR * (1.0 - expected_win)).round() as u32;
+
+    (winner_rating + change, loser_rating.saturating_sub(change))
+}
+
+fn get_rating_window(waiting_since: Option<Timestamp>, time: Timestamp) -> u32 {
+    let minutes_waiting = waiting_since
+        .map(|waiting_since| time.saturating_sub(waiting_since) / MINUTE)
+        .unwrap_or(0);
+
+    RATING_WINDOW.saturating_add((minutes_waiting as u32).saturating_mul(RATING_WINDOW_GROWTH))
+}
+
+impl Hockey {
+    pub(crate) fn internal_update_ratings(&mut self, winner_id: &AccountId, loser_id: &AccountId) {
+        let mut winner_stats = self.internal_get_stats(winner_id);
+        let mut loser_stats = self.internal_get_stats(loser_id);
+        let winner_scores = self.internal_get_scores(winner_id, &winner_stats);
+        let loser_scores = self.internal_get_scores(loser_id, &loser_stats);
+
+        let (winner_rating, loser_rating) = get_new_ratings(winner_stats.rating, loser_stats.rating);
+        winner_stats.rating = winner_rating;
+        loser_stats.rating = loser_rating;
+
+        self.internal_update_leaderboards(winner_id, winner_scores, &winner_stats);
+        self.internal_update_leaderboards(loser_id, loser_scores, &loser_stats);
+        self.stats.insert(winner_id, &VStats::Current(winner_stats));
+        self.stats.insert(loser_id, &VStats::Current(loser_stats));
+    }
+
+    /// The waiting player with the same rules and stake token, and the closest rating.
+    /// Players waiting for another opponent are skipped.
+    /// Every waiting player accepts a rating difference that grows the longer they wait.
+    /// Only the first `MAX_CHECKED_OPPONENTS` waiting players are checked
+    pub(crate) fn internal_find_opponent(
+        &self,
+        account_id: &AccountId,
+        config: &GameConfig,
+        available_players: &UnorderedMap<AccountId, VGameConfig>
+    ) -> Option<AccountId> {
+        let rating = self.internal_get_stats(account_id).rating;
+        let rules = config.get_rules();
+        let time = env::block_timestamp();
+
+        available_players.iter()
+            .take(MAX_CHECKED_OPPONENTS)
+            .map(|(opponent_id, opponent_config)| (opponent_id, GameConfig::from(opponent_config)))
+            .filter(|(_, opponent_config)| opponent_config.get_rules() == rules
+                && opponent_config.token_id == config.token_id
+                && opponent_config.opponent_id.iter().all(|opponent_id| opponent_id == account_id))
+            .map(|(opponent_id, opponent_config)| {
+                let opponent_rating = self.internal_get_stats(&opponent_id).rating;
+                let difference = rating.abs_diff(opponent_rating);
+
+                (opponent_id, difference, get_rating_window(opponent_config.waiting_since, time))
+            })
+            .filter(|(_, difference, window)| difference <= window)
+            .min_by_key(|(_, difference, _)| *difference)
+            .map(|(opponent_id, _, _)| opponent_id)
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn get_rating(&self, account_id: AccountId) -> u32 {
+        self.internal_get_stats(&account_id).rating
+    }
+
+    /// Sorted by rating
+    pub fn get_rating_leaderboard(&self, from_index: u64, limit: u64) -> Vec<(AccountId, u32)> {
+        self.get_leaderboard(LeaderboardType::Rating, from_index, limit)
+            .into_iter()
+            .map(|(account_id, rating)| (account_id, rating.0 as u32))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::game::match_rules::{MatchRules, VMatchRules};
+    use crate::manager::Stats;
+    use crate::test_utils::set_context;
+
+    fn get_config(waiting_since: Option<Timestamp>) -> GameConfig {
+        GameConfig {
+            deposit: Some(1),
+            opponent_id: None,
+            rules: None,
+            waiting_since,
+            token_id: None,
+        }
+    }
+
+    fn set_rating(hockey: &mut Hockey, account_id: &AccountId, rating: u32) {
+        let mut stats = Stats::new(account_id);
+        stats.rating = rating;
+        hockey.stats.insert(account_id, &VStats::Current(stats));
+    }
+
+    fn get_available_players(players: &[(AccountId, GameConfig)]) -> UnorderedMap<AccountId, VGameConfig> {
+        let mut available_players = UnorderedMap::new(b"p".to_vec());
+        for (account_id, config) in players {
+            available_players.insert(account_id, &VGameConfig::Current(config.clone()));
+        }
+        available_players
+    }
+
+    #[test]
+    fn winner_takes_more_from_a_stronger_opponent() {
+        assert_eq!(get_new_ratings(1500, 1500), (1516, 1484));
+        assert_eq!(get_new_ratings(1400, 1600), (1424, 1576));
+        assert_eq!(get_new_ratings(1600, 1400), (1608, 1392));
+        assert_eq!(get_new_ratings(1500, 10), (1500, 10));
+        assert_eq!(get_new_ratings(100, 5), (112, 0));
+    }
+
+    #[test]
+    fn rating_window_grows_every_minute() {
+        assert_eq!(get_rating_window(None, 10 * MINUTE), RATING_WINDOW);
+        assert_eq!(get_rating_window(Some(MINUTE), MINUTE + 59 * SECOND), RATING_WINDOW);
+        assert_eq!(get_rating_window(Some(MINUTE), 4 * MINUTE), RATING_WINDOW + 3 * RATING_WINDOW_GROWTH);
+        assert_eq!(get_rating_window(Some(2 * MINUTE), MINUTE), RATING_WINDOW);
+    }
+
+    #[test]
+    fn finished_game_updates_both_ratings() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_rating(&mut hockey, &accounts(2), 1600);
+
+        hockey.internal_update_ratings(&accounts(1), &accounts(2));
+
+        assert_eq!(hockey.get_rating(accounts(1)), 1520);
+        assert_eq!(hockey.get_rating(accounts(2)), 1580);
+    }
+
+    #[test]
+    fn closest_rating_in_the_window_is_chosen() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .block_timestamp(2 * MINUTE)
+            .build());
+        let mut hockey = Hockey::new(accounts(0));
+        set_rating(&mut hockey, &accounts(2), 1700);
+        set_rating(&mut hockey, &accounts(3), 1580);
+        set_rating(&mut hockey, &accounts(4), 1550);
+
+        let short_rules = MatchRules { first_period: 5, second_period: 10, third_period: 15, overtime_period: 20, ..MatchRules::default() };
+        let other_rules = GameConfig { rules: Some(VMatchRules::Current(short_rules)), ..get_config(None) };
+        let available_players = get_available_players(&[
+            (accounts(2), get_config(Some(0))),
+            (accounts(3), get_config(None)),
+            (accounts(4), other_rules),
+        ]);
+
+        let opponent_id = hockey.internal_find_opponent(&accounts(1), &get_config(None), &available_players);
+        assert_eq!(opponent_id, Some(accounts(3)));
+
+        // The window of a player waiting for two minutes covers the difference of 200
+        set_rating(&mut hockey, &accounts(3), 1200);
+        let opponent_id = hockey.internal_find_opponent(&accounts(1), &get_config(None), &available_players);
+        assert_eq!(opponent_id, Some(accounts(2)));
+    }
+
+    #[test]
+    fn players_waiting_for_someone_else_are_skipped() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        set_rating(&mut hockey, &accounts(3), 1550);
+
+        let waiting_for = |account_id: AccountId| GameConfig { opponent_id: Some(account_id), ..get_config(None) };
+        let mut available_players = get_available_players(&[
+            (accounts(2), waiting_for(accounts(4))),
+            (accounts(3), get_config(None)),
+        ]);
+        let opponent_id = hockey.internal_find_opponent(&accounts(1), &get_config(None), &available_players);
+        assert_eq!(opponent_id, Some(accounts(3)));
+
+        available_players.insert(&accounts(2), &VGameConfig::Current(waiting_for(accounts(1))));
+        let opponent_id = hockey.internal_find_opponent(&accounts(1), &get_config(None), &available_players);
+        assert_eq!(opponent_id, Some(accounts(2)));
+    }
+
+    #[test]
+    fn only_first_waiting_players_are_checked() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+
+        let mut players: Vec<(AccountId, GameConfig)> = (0..MAX_CHECKED_OPPONENTS)
+            .map(|index| {
+                let account_id: AccountId = format!("player{}.near", index).parse().unwrap();
+                set_rating(&mut hockey, &account_id, 2000);
+                (account_id, get_config(None))
+            })
+            .collect();
+        players.push((accounts(2), get_config(None)));
+        let available_players = get_available_players(&players);
+
+        assert!(hockey.internal_find_opponent(&accounts(1), &get_config(None), &available_players).is_none());
+    }
+}
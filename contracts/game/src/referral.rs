@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::owner::MAX_BPS;
+
+pub const MAX_REFERRAL_LEVELS: usize = 5;
+
+/// What an affiliate produced for one referrer
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AffiliateEarnings {
+    // 1 for direct affiliates
+    pub(crate) level: u8,
+    // NEAR has no token id and comes first, tokens are sorted by id
+    pub(crate) earnings: BTreeMap<Option<AccountId>, Balance>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AffiliateOutput {
+    account_id: AccountId,
+    level: u8,
+    earnings: Vec<(Option<AccountId>, U128)>,
+}
+
+impl Hockey {
+    /// Referrers of the account, the direct one first
+    fn internal_get_referrers(&self, account_id: &AccountId, max_levels: usize) -> Vec<AccountId> {
+        let mut referrers = vec![];
+        let mut current_id = account_id.clone();
+
+        while referrers.len() < max_levels {
+            match self.internal_get_stats(&current_id).referrer_id {
+                Some(referrer_id) => {
+                    referrers.push(referrer_id.clone());
+                    current_id = referrer_id;
+                },
+                None => break,
+            }
+        }
+
+        referrers
+    }
+
+    fn internal_add_affiliate_earnings(
+        &mut self,
+        referrer_id: &AccountId,
+        affiliate_id: &AccountId,
+        level: u8,
+        token_id: &Option<AccountId>,
+        amount: Balance
+    ) {
+        let mut affiliates = self.affiliate_earnings.get(referrer_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::AffiliateEarningsPerReferrer { account_id: hash_account_id(referrer_id.as_str()) }.try_to_vec().unwrap())
+        });
+
+        let mut affiliate_earnings = affiliates.get(affiliate_id).unwrap_or(AffiliateEarnings {
+            level,
+            earnings: BTreeMap::new(),
+        });
+        if amount > 0 {
+            *affiliate_earnings.earnings.entry(token_id.clone()).or_insert(0) += amount;
+        }
+
+        affiliates.insert(affiliate_id, &affiliate_earnings);
+        self.affiliate_earnings.insert(referrer_id, &affiliates);
+    }
+
+    /// Pays every level of referrers of the winner its share of the fee. Returns the total paid
+    pub(crate) fn internal_pay_referrers(&mut self, winner_id: &AccountId, token_id: &Option<AccountId>, fee: Balance) -> Balance {
+        let referral_tiers = self.contract_config.referral_tiers.clone();
+        let referrers = self.internal_get_referrers(winner_id, referral_tiers.len());
+
+        let mut total_referrer_fee = 0;
+        for (index, referrer_id) in referrers.iter().enumerate() {
+            let level = index + 1;
+            let referrer_fee = fee * referral_tiers[index] as Balance / MAX_BPS as Balance;
+            log!("Affiliate reward of level {} for {} is {}", level, referrer_id, referrer_fee);
+
+            self.internal_update_stats(referrer_id, UpdateStatsAction::AddAffiliateReward, token_id.clone(), Some(referrer_fee));
+            self.internal_add_affiliate_earnings(referrer_id, winner_id, level as u8, token_id, referrer_fee);
+            self.internal_credit(referrer_id, token_id, referrer_fee);
+            total_referrer_fee += referrer_fee;
+        }
+
+        total_referrer_fee
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// The referrer can be set once. It must have played before
+    pub fn register_referrer(&mut self, referrer_id: AccountId) {
+        let account_id = predecessor_account_id();
+        assert_ne!(account_id, referrer_id, "You cannot refer yourself");
+        assert!(self.internal_get_stats(&account_id).referrer_id.is_none(), "Referrer is already set");
+        assert!(self.is_account_exists(&Some(referrer_id.clone())), "Referrer not found");
+
+        let referrers = self.internal_get_referrers(&referrer_id, MAX_REFERRAL_LEVELS);
+        assert!(!referrers.contains(&account_id), "Referral cycles are not allowed");
+
+        self.internal_update_stats(&account_id, UpdateStatsAction::AddReferral, Some(referrer_id.clone()), None);
+        self.internal_update_stats(&referrer_id, UpdateStatsAction::AddAffiliate, Some(account_id.clone()), None);
+
+        // The affiliate is listed for every referrer that gets a share of its rewards
+        let levels = self.contract_config.referral_tiers.len();
+        for (index, upper_referrer_id) in std::iter::once(referrer_id.clone()).chain(referrers).take(levels).enumerate() {
+            self.internal_add_affiliate_earnings(&upper_referrer_id, &account_id, index as u8 + 1, &None, 0);
+        }
+
+        log!("Referrer {} added for {}", referrer_id, account_id);
+    }
+
+    /// Affiliates of all levels and the rewards they produced for the referrer
+    pub fn get_affiliates(&self, referrer_id: AccountId, from_index: u64, limit: u64) -> Vec<AffiliateOutput> {
+        let affiliates = match self.affiliate_earnings.get(&referrer_id) {
+            Some(affiliates) => affiliates,
+            None => return vec![],
+        };
+
+        let keys = affiliates.keys_as_vector();
+        let values = affiliates.values_as_vector();
+        (from_index..std::cmp::min(from_index + limit, keys.len()))
+            .map(|index| {
+                let affiliate_earnings = values.get(index).unwrap();
+                AffiliateOutput {
+                    account_id: keys.get(index).unwrap(),
+                    level: affiliate_earnings.level,
+                    earnings: affiliate_earnings.earnings.into_iter()
+                        .map(|(token_id, amount)| (token_id, U128::from(amount)))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::manager::{Stats, VStats};
+    use crate::test_utils::{get_reward, set_context};
+
+    /// `accounts(1)` is referred by `accounts(2)`, `accounts(2)` by `accounts(3)`
+    /// and `accounts(3)` by `accounts(4)`
+    fn get_contract_with_referrers() -> Hockey {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        for index in 1..=4 {
+            hockey.stats.insert(&accounts(index), &VStats::Current(Stats::new(&accounts(index))));
+        }
+
+        for index in (1..=3).rev() {
+            set_context(accounts(index));
+            hockey.register_referrer(accounts(index + 1));
+        }
+        set_context(accounts(0));
+
+        hockey
+    }
+
+    fn get_claimable(hockey: &Hockey, account_id: AccountId, token_id: Option<AccountId>) -> Balance {
+        hockey.get_claimable(account_id).into_iter()
+            .find(|(balance_token_id, _)| *balance_token_id == token_id)
+            .map(|(_, balance)| balance.0)
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn every_tier_gets_its_share_of_the_fee() {
+        let mut hockey = get_contract_with_referrers();
+
+        let reward = hockey.internal_distribute_reward(&get_reward(1_000), &accounts(1), 1);
+
+        assert_eq!(reward, 900);
+        assert_eq!(get_claimable(&hockey, accounts(2), None), 40);
+        assert_eq!(get_claimable(&hockey, accounts(3), None), 10);
+        assert_eq!(get_claimable(&hockey, accounts(4), None), 0);
+        assert_eq!(hockey.get_service_fee(), U128::from(50));
+        assert_eq!(hockey.internal_get_stats(&accounts(2)).total_affiliate_reward.get(&None), Some(40));
+    }
+
+    #[test]
+    fn more_tiers_reach_further_referrers() {
+        let mut hockey = get_contract_with_referrers();
+        hockey.set_referral_tiers(vec![5_000, 2_000, 1_000]);
+
+        assert_eq!(hockey.internal_pay_referrers(&accounts(1), &None, 100), 80);
+        assert_eq!(get_claimable(&hockey, accounts(2), None), 50);
+        assert_eq!(get_claimable(&hockey, accounts(3), None), 20);
+        assert_eq!(get_claimable(&hockey, accounts(4), None), 10);
+    }
+
+    #[test]
+    fn winner_without_referrer_pays_nobody() {
+        let mut hockey = get_contract_with_referrers();
+
+        assert_eq!(hockey.internal_pay_referrers(&accounts(4), &None, 100), 0);
+        assert!(hockey.get_claimable(accounts(4)).is_empty());
+    }
+
+    #[test]
+    fn earnings_are_listed_by_affiliate_and_token() {
+        let mut hockey = get_contract_with_referrers();
+        let token_id = Some(accounts(5));
+        hockey.internal_pay_referrers(&accounts(1), &None, 100);
+        hockey.internal_pay_referrers(&accounts(1), &token_id, 200);
+
+        let affiliates = hockey.get_affiliates(accounts(3), 0, 10);
+        let affiliate = affiliates.iter().find(|affiliate| affiliate.account_id == accounts(1)).expect("Affiliate not found");
+        assert_eq!(affiliate.level, 2);
+        assert_eq!(affiliate.earnings, vec![(None, U128::from(10)), (token_id.clone(), U128::from(20))]);
+        assert_eq!(get_claimable(&hockey, accounts(3), token_id), 20);
+
+        // Affiliates below the paid tiers are not listed
+        assert!(hockey.get_affiliates(accounts(4), 0, 10).iter().all(|affiliate| affiliate.account_id != accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Referral cycles are not allowed")]
+    fn referral_cycle() {
+        let mut hockey = get_contract_with_referrers();
+
+        set_context(accounts(4));
+        hockey.register_referrer(accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Referrer is already set")]
+    fn referrer_is_set_once() {
+        let mut hockey = get_contract_with_referrers();
+
+        set_context(accounts(1));
+        hockey.register_referrer(accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Referrer not found")]
+    fn referrer_without_games() {
+        set_context(accounts(1));
+        let mut hockey = Hockey::new(accounts(0));
+        hockey.register_referrer(accounts(2));
+    }
+}
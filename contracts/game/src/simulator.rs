@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fs;
+use std::panic;
+use near_sdk::{AccountId, serde_json, testing_env};
+use near_sdk::serde::Serialize;
+use near_sdk::test_utils::VMContextBuilder;
+use crate::{SECOND, TokenBalance};
+use crate::game::actions::action::{ActionData, ActionTypes};
+use crate::game::game::{Event, Game, GameState};
+use crate::game::match_rules::MatchRules;
+use crate::game::rng::{Rng, SeededRng};
+use crate::team::team_metadata::TeamMetadata;
+
+const USAGE: &str = "Usage: simulator <team1.json> <team2.json> [--games N] [--seed SEED] [--rules RULES]";
+
+// A game that is still running after this number of events is reported as unfinished
+const MAX_EVENTS_PER_GAME: u64 = 1000;
+
+const TEAM1_ACCOUNT: &str = "team1.simulator";
+const TEAM2_ACCOUNT: &str = "team2.simulator";
+
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TeamReport {
+    pub account_id: String,
+    pub wins: u64,
+    pub win_rate: f32,
+    pub goals_per_game: f32,
+    pub shots_per_game: f32,
+    pub penalties_per_game: f32,
+}
+
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SimulationReport {
+    pub games: u64,
+    pub finished_games: u64,
+    pub failed_games: u64,
+    pub overtime_games: u64,
+    pub shootout_games: u64,
+    pub goals_per_game: f32,
+    pub shots_per_game: f32,
+    pub penalties_per_game: f32,
+    pub teams: Vec<TeamReport>,
+    // Panic message -> number of games it ended
+    pub errors: HashMap<String, u64>,
+}
+
+#[derive(Default)]
+struct GameTotals {
+    goals: u64,
+    shots: u64,
+    penalties: u64,
+}
+
+pub fn run(args: Vec<String>) {
+    let mut files: Vec<String> = Vec::new();
+    let mut games: Option<u64> = None;
+    let mut seed: u64 = 0;
+    let mut rules = MatchRules::default();
+
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => games = Some(parse_number(args.next())),
+            "--seed" => seed = parse_number(args.next()),
+            "--rules" => rules = load_rules(&args.next().unwrap_or_else(|| panic!("{}", USAGE))),
+            _ => files.push(arg),
+        }
+    }
+
+    if files.len() != 2 {
+        panic!("{}", USAGE);
+    }
+
+    let team1 = load_team(&files[0]);
+    let team2 = load_team(&files[1]);
+
+    match games {
+        None => {
+            simulate_game(&team1, &team2, &rules, seed, |event| {
+                println!("{}", serde_json::to_string(event).expect("Cannot serialize event"));
+            });
+        },
+        Some(number_of_games) => {
+            let report = simulate_games(&team1, &team2, &rules, number_of_games, seed);
+            println!("{}", serde_json::to_string_pretty(&report).expect("Cannot serialize report"));
+        }
+    }
+}
+
+fn parse_number(value: Option<String>) -> u64 {
+    value.and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{}", USAGE))
+}
+
+pub fn load_team(path: &str) -> TeamMetadata {
+    let json = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {}", path, e));
+    serde_json::from_str(&json)
+        .unwrap_or_else(|e| panic!("Cannot parse {}: {}", path, e))
+}
+
+pub fn load_rules(path: &str) -> MatchRules {
+    let json = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {}", path, e));
+    let rules: MatchRules = serde_json::from_str(&json)
+        .unwrap_or_else(|e| panic!("Cannot parse {}: {}", path, e));
+
+    rules.assert_valid();
+    rules
+}
+
+fn set_block(seed: u64, block: u64) {
+    let mut random_seed = [0u8; 32];
+    random_seed[..8].copy_from_slice(&seed.to_le_bytes());
+    random_seed[8..16].copy_from_slice(&block.to_le_bytes());
+
+    testing_env!(VMContextBuilder::new()
+        .block_index(block)
+        .block_timestamp(block * SECOND)
+        .random_seed(random_seed)
+        .build());
+}
+
+/// Plays one match with a `SeededRng`. The same teams and seed always give the same events
+pub fn simulate_game<F: FnMut(&Event)>(
+    team1: &TeamMetadata,
+    team2: &TeamMetadata,
+    rules: &MatchRules,
+    seed: u64,
+    mut on_event: F
+) {
+    set_block(seed, 0);
+
+    let teams = (team1.clone(), team2.clone());
+    let mut game = Game::new(
+        teams,
+        AccountId::new_unchecked(TEAM1_ACCOUNT.into()),
+        AccountId::new_unchecked(TEAM2_ACCOUNT.into()),
+        TokenBalance { token_id: None, balance: 0 },
+        &0,
+        Rng::Seeded(SeededRng::new(seed.to_le_bytes().to_vec())),
+        rules.clone(),
+    );
+
+    let mut number_of_events: u64 = 0;
+    while game.winner_index.is_none() && number_of_events < MAX_EVENTS_PER_GAME {
+        // Every event is generated in its own block, as on chain
+        number_of_events += 1;
+        set_block(seed, number_of_events);
+        game.last_event_generation_time = near_sdk::env::block_timestamp();
+
+        on_event(&next_event(&mut game));
+    }
+}
+
+fn next_event(game: &mut Game) -> Event {
+    let mut generated_actions = game.step();
+
+    let game_state = game.get_game_state();
+    if let Some(action) = game_state.1 {
+        generated_actions.push(action);
+    }
+
+    if let GameState::GameOver { winner_id } = game_state.0 {
+        generated_actions.push(ActionData::GameFinished {
+            action_type: ActionTypes::GameFinished,
+            winner_account_id: game.get_user_info(winner_id).account_id.clone(),
+            reward: 0,
+            box_score: game.box_score.clone(),
+        });
+
+        game.winner_index = Some(winner_id);
+    }
+
+    game.generate_event(&mut generated_actions)
+}
+
+pub fn simulate_games(
+    team1: &TeamMetadata,
+    team2: &TeamMetadata,
+    rules: &MatchRules,
+    games: u64,
+    seed: u64
+) -> SimulationReport {
+    let mut report = SimulationReport { games, ..Default::default() };
+    let mut wins: HashMap<AccountId, u64> = HashMap::new();
+    let mut totals: HashMap<AccountId, GameTotals> = HashMap::new();
+
+    // Engine panics are counted instead of aborting the whole run
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    for game_number in 0..games {
+        let mut actions: Vec<ActionData> = Vec::new();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            simulate_game(team1, team2, rules, seed.wrapping_add(game_number), |event| {
+                actions.extend(event.actions.iter().cloned());
+            })
+        }));
+
+        if let Err(error) = result {
+            let message = error.downcast_ref::<String>().cloned()
+                .or_else(|| error.downcast_ref::<&str>().map(|message| message.to_string()))
+                .unwrap_or_default();
+            *report.errors.entry(message).or_insert(0) += 1;
+            report.failed_games += 1;
+            continue;
+        }
+
+        let mut is_finished = false;
+        for action in &actions {
+            match action {
+                ActionData::Goal { account_id, .. } => {
+                    totals.entry(account_id.clone()).or_default().goals += 1;
+                },
+                ActionData::Shot { account_id, .. } => {
+                    totals.entry(account_id.clone()).or_default().shots += 1;
+                },
+                ActionData::Penalty { account_id, .. } => {
+                    totals.entry(account_id.clone()).or_default().penalties += 1;
+                },
+                ActionData::Overtime { .. } => report.overtime_games += 1,
+                ActionData::Shootout { .. } => report.shootout_games += 1,
+                ActionData::GameFinished { winner_account_id, .. } => {
+                    *wins.entry(winner_account_id.clone()).or_insert(0) += 1;
+                    is_finished = true;
+                },
+                _ => {}
+            }
+        }
+
+        if is_finished {
+            report.finished_games += 1;
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    let played_games = (games - report.failed_games).max(1) as f32;
+    for account in [TEAM1_ACCOUNT, TEAM2_ACCOUNT] {
+        let account_id = AccountId::new_unchecked(account.into());
+        let team_totals = totals.remove(&account_id).unwrap_or_default();
+        let team_wins = wins.get(&account_id).copied().unwrap_or(0);
+
+        report.goals_per_game += team_totals.goals as f32 / played_games;
+        report.shots_per_game += team_totals.shots as f32 / played_games;
+        report.penalties_per_game += team_totals.penalties as f32 / played_games;
+
+        report.teams.push(TeamReport {
+            account_id: account.into(),
+            wins: team_wins,
+            win_rate: team_wins as f32 / played_games,
+            goals_per_game: team_totals.goals as f32 / played_games,
+            shots_per_game: team_totals.shots as f32 / played_games,
+            penalties_per_game: team_totals.penalties as f32 / played_games,
+        });
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::bot::{generate_bot_team, BotDifficulty};
+    use crate::test_utils::get_team_metadata;
+
+    fn get_wins(report: &SimulationReport) -> Vec<u64> {
+        report.teams.iter().map(|team| team.wins).collect()
+    }
+
+    fn get_errors(report: &SimulationReport) -> BTreeMap<String, u64> {
+        report.errors.clone().into_iter().collect()
+    }
+
+    #[test]
+    fn seeded_run_is_reproducible() {
+        let team1 = generate_bot_team(BotDifficulty::Medium);
+        let team2 = generate_bot_team(BotDifficulty::Hard);
+
+        let report = simulate_games(&team1, &team2, &MatchRules::default(), 12, 7);
+        let same_report = simulate_games(&team1, &team2, &MatchRules::default(), 12, 7);
+
+        // Most of the failures are engine panics on full rosters, they are counted by message
+        assert_eq!((report.finished_games, report.failed_games), (3, 9));
+        assert_eq!(get_wins(&report), vec![0, 3]);
+        assert_eq!(get_errors(&report), BTreeMap::from([
+            ("Player not found: bot-17".to_string(), 1),
+            ("Player not found: bot-7".to_string(), 2),
+            ("Player not found: bot-8".to_string(), 2),
+            ("attempt to subtract with overflow".to_string(), 4),
+        ]));
+
+        assert_eq!((same_report.finished_games, same_report.failed_games), (3, 9));
+        assert_eq!(get_wins(&same_report), get_wins(&report));
+        assert_eq!(get_errors(&same_report), get_errors(&report));
+        assert_eq!(same_report.goals_per_game, report.goals_per_game);
+    }
+
+    #[test]
+    fn every_game_is_either_won_or_failed() {
+        let team = generate_bot_team(BotDifficulty::Easy);
+        let report = simulate_games(&team, &team, &MatchRules::default(), 10, 100);
+
+        assert_eq!(report.games, 10);
+        assert_eq!(report.finished_games + report.failed_games, report.games);
+        assert_eq!(get_wins(&report).iter().sum::<u64>(), report.finished_games);
+        assert_eq!(report.errors.values().sum::<u64>(), report.failed_games);
+    }
+
+    #[test]
+    fn team_with_one_five_fails_every_game() {
+        let report = simulate_games(&get_team_metadata("a"), &get_team_metadata("b"), &MatchRules::default(), 4, 7);
+
+        assert_eq!((report.finished_games, report.failed_games), (0, 4));
+        assert_eq!(get_wins(&report), vec![0, 0]);
+        assert_eq!(get_errors(&report), BTreeMap::from([("Five not found".to_string(), 4)]));
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_events() {
+        let team1 = generate_bot_team(BotDifficulty::Medium);
+        let team2 = generate_bot_team(BotDifficulty::Medium);
+        let play = || {
+            let mut events = Vec::new();
+            let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                simulate_game(&team1, &team2, &MatchRules::default(), 3, |event| {
+                    events.push(serde_json::to_string(event).unwrap());
+                })
+            }));
+            events
+        };
+
+        let events = play();
+        assert!(!events.is_empty());
+        assert_eq!(play(), events);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::*;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TeamScore {
+    account_id: AccountId,
+    score: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GameScore {
+    game_id: GameId,
+    team1: TeamScore,
+    team2: TeamScore,
+    period: u8,
+    turns: u8,
+    is_shootout: bool,
+    zone_number: u8,
+    // Account and token id of the player with the puck
+    player_with_puck: Option<(AccountId, TokenId)>,
+    winner_account_id: Option<AccountId>,
+}
+
+impl From<&Game> for GameScore {
+    fn from(game: &Game) -> Self {
+        let player_with_puck = game.player_with_puck.as_ref()
+            .map(|(user_id, player_id)| (game.get_user_info(*user_id).account_id.clone(), player_id.clone()));
+
+        GameScore {
+            game_id: game.game_id,
+            team1: TeamScore {
+                account_id: game.user1.account_id.clone(),
+                score: game.user1.team.score,
+            },
+            team2: TeamScore {
+                account_id: game.user2.account_id.clone(),
+                score: game.user2.team.score,
+            },
+            period: game.get_period(),
+            turns: game.turns,
+            is_shootout: game.shootout.is_some(),
+            zone_number: game.zone_number,
+            player_with_puck,
+            winner_account_id: game.winner_index
+                .map(|winner_index| game.get_user_info(winner_index).account_id.clone()),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    pub fn get_game(&self, game_id: GameId) -> Game {
+        self.internal_get_game(&game_id)
+    }
+
+    pub fn get_live_score(&self, game_id: GameId) -> GameScore {
+        let game = self.internal_get_game(&game_id);
+        GameScore::from(&game)
+    }
+
+    /// The game the account is playing now, if any
+    pub fn get_active_game(&self, account_id: AccountId) -> Option<GameId> {
+        self.active_games.get(&account_id)
+    }
+
+    /// In the order the games finished
+    pub fn get_finished_games(&self, from_index: u64, limit: u64) -> Vec<GameScore> {
+        (from_index..std::cmp::min(from_index + limit, self.finished_games.len()))
+            .map(|index| {
+                let game_id = self.finished_games.get(index).unwrap();
+                GameScore::from(&self.internal_get_game(&game_id))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+    use crate::manager::GameConfig;
+    use crate::user_info::{USER_ID1, USER_ID2};
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    fn start_game(hockey: &mut Hockey, account_id: AccountId, opponent_id: AccountId) -> GameId {
+        let config = GameConfig {
+            deposit: None,
+            opponent_id: None,
+            rules: None,
+            waiting_since: None,
+            token_id: None,
+        };
+        hockey.init_game(opponent_id, account_id, config, (get_team_metadata("a"), get_team_metadata("b"))).game_id
+    }
+
+    fn finish_game(hockey: &mut Hockey, game_id: GameId, winner_index: UserId) {
+        let mut game = hockey.internal_get_game(&game_id);
+        hockey.internal_finish_game(&mut game, winner_index);
+        hockey.internal_save_game(game);
+    }
+
+    #[test]
+    fn active_game_of_both_players() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let game_id = start_game(&mut hockey, accounts(1), accounts(2));
+
+        assert_eq!(hockey.get_active_game(accounts(1)), Some(game_id));
+        assert_eq!(hockey.get_active_game(accounts(2)), Some(game_id));
+        assert_eq!(hockey.get_active_game(accounts(3)), None);
+    }
+
+    #[test]
+    fn no_active_game_after_it_finished() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let game_id = start_game(&mut hockey, accounts(1), accounts(2));
+        let other_game_id = start_game(&mut hockey, accounts(3), accounts(4));
+
+        finish_game(&mut hockey, game_id, USER_ID1);
+
+        assert_eq!(hockey.get_active_game(accounts(1)), None);
+        assert_eq!(hockey.get_active_game(accounts(2)), None);
+        assert_eq!(hockey.get_active_game(accounts(3)), Some(other_game_id));
+
+        let next_game_id = start_game(&mut hockey, accounts(2), accounts(1));
+        assert_eq!(hockey.get_active_game(accounts(1)), Some(next_game_id));
+    }
+
+    #[test]
+    fn finished_games_by_pages() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let first_game_id = start_game(&mut hockey, accounts(1), accounts(2));
+        let second_game_id = start_game(&mut hockey, accounts(3), accounts(4));
+        let third_game_id = start_game(&mut hockey, accounts(5), accounts(0));
+
+        // Not in the order the games started
+        finish_game(&mut hockey, second_game_id, USER_ID2);
+        finish_game(&mut hockey, first_game_id, USER_ID1);
+        finish_game(&mut hockey, third_game_id, USER_ID1);
+
+        let game_ids = |scores: Vec<GameScore>| -> Vec<GameId> {
+            scores.iter().map(|score| score.game_id).collect()
+        };
+        assert_eq!(game_ids(hockey.get_finished_games(0, 2)), vec![second_game_id, first_game_id]);
+        assert_eq!(game_ids(hockey.get_finished_games(2, 2)), vec![third_game_id]);
+        assert!(hockey.get_finished_games(3, 2).is_empty());
+        assert!(hockey.get_finished_games(0, 0).is_empty());
+
+        let score = &hockey.get_finished_games(0, 1)[0];
+        assert_eq!(score.team1.account_id, accounts(3));
+        assert_eq!(score.winner_account_id, Some(accounts(4)));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::*;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use crate::PlayerPosition::{LeftDefender, RightDefender};
 use crate::team::numbers::FiveNumber;
 use crate::team::players::player::PlayerRole;
@@ -20,7 +20,7 @@ pub struct ActiveFive {
     pub(crate) current_number: FiveNumber,
     pub(crate) replaced_position: Vec<PlayerPosition>,
 
-    pub(crate) field_players: HashMap<PlayerPosition, TokenId>,
+    pub(crate) field_players: BTreeMap<PlayerPosition, TokenId>,
     pub(crate) is_goalie_out: bool,
     pub(crate) ice_time_priority: IceTimePriority,
     pub(crate) tactic: Tactics,
@@ -49,7 +49,7 @@ impl ActiveFive {
 #[derive(Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct FiveIds {
-    pub(crate) field_players: HashMap<PlayerPosition, TokenId>,
+    pub(crate) field_players: BTreeMap<PlayerPosition, TokenId>,
     pub(crate) number: FiveNumber,
     pub(crate) ice_time_priority: IceTimePriority,
     pub(crate) tactic: Tactics,
@@ -57,9 +57,9 @@ pub struct FiveIds {
 
 // teamwork
 impl FiveIds {
-    pub fn calculate_team_work(&self, field_players: &mut HashMap<TokenId, FieldPlayer>) {
-        let mut player_per_nationality: HashMap<String, Vec<PlayerPosition>> = HashMap::new();
-        let mut player_per_role: HashMap<PlayerRole, Vec<PlayerPosition>> = HashMap::new();
+    pub fn calculate_team_work(&self, field_players: &mut BTreeMap<TokenId, FieldPlayer>) {
+        let mut player_per_nationality: BTreeMap<String, Vec<PlayerPosition>> = BTreeMap::new();
+        let mut player_per_role: BTreeMap<PlayerRole, Vec<PlayerPosition>> = BTreeMap::new();
 
         let mut team_work_line: f32 = 1.0;
 
@@ -84,7 +84,7 @@ impl FiveIds {
 
     fn insert_player_nationality(
         &self,
-        player_per_nationality: &mut HashMap<String, Vec<PlayerPosition>>,
+        player_per_nationality: &mut BTreeMap<String, Vec<PlayerPosition>>,
         field_player: &FieldPlayer,
         position: &PlayerPosition
     ) {
@@ -100,7 +100,7 @@ impl FiveIds {
 
     fn insert_player_role(
         &self,
-        player_per_role: &mut HashMap<PlayerRole, Vec<PlayerPosition>>,
+        player_per_role: &mut BTreeMap<PlayerRole, Vec<PlayerPosition>>,
         field_player: &FieldPlayer,
         position: &PlayerPosition
     ) {
@@ -116,8 +116,8 @@ impl FiveIds {
 
     fn change_teamwork_by_roles(
         &self,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         self.check_offensive_defensive_defensemen(player_per_role, field_players);
         self.check_enf_tough(&player_per_role, field_players);
@@ -126,8 +126,8 @@ impl FiveIds {
 
     fn check_offensive_defensive_defensemen(
         &self,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         match player_per_role.get(&DefensiveDefenseman) {
             Some(def_positions) => {
@@ -152,7 +152,7 @@ impl FiveIds {
         &self,
         position: &PlayerPosition,
         teamwork: f32,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         let token_id = self.field_players.get(position).unwrap();
         let player = field_players.get_mut(token_id).unwrap();
@@ -161,8 +161,8 @@ impl FiveIds {
 
     fn check_enf_tough(
         &self,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         if player_per_role.get(&ToughGuy).is_some() {
             self.change_team_work_by_enf_tough(player_per_role, &ToughGuy, field_players);
@@ -174,9 +174,9 @@ impl FiveIds {
 
     fn change_team_work_by_enf_tough(
         &self,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
         player_role: &PlayerRole,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         let number_of_tough = player_per_role.get(player_role).unwrap().len();
         let teamwork = TOUGH_ENFORCER_TEAMWORK * (number_of_tough as f32);
@@ -188,8 +188,8 @@ impl FiveIds {
         &self,
         teamwork: f32,
         player_role: &PlayerRole,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         match player_per_role.get(&player_role) {
             Some(playmaker_positions) => {
@@ -203,8 +203,8 @@ impl FiveIds {
 
     fn check_defensive_forward(
         &self,
-        player_per_role: &HashMap<PlayerRole, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_role: &BTreeMap<PlayerRole, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         if player_per_role.get(&DefensiveForward).is_some() {
             self.change_teamwork_by_position(&LeftDefender, DEFENDERS_TEAMWORK, field_players);
@@ -214,8 +214,8 @@ impl FiveIds {
 
     fn change_teamwork_by_nationality(
         &self,
-        player_per_nationality: &HashMap<String, Vec<PlayerPosition>>,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        player_per_nationality: &BTreeMap<String, Vec<PlayerPosition>>,
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         for (_nationality, positions) in player_per_nationality {
             if positions.len() > 1 {
@@ -228,7 +228,7 @@ impl FiveIds {
 
     fn change_line_teamwork(
         &self, team_work_line: f32,
-        field_players: &mut HashMap<TokenId, FieldPlayer>
+        field_players: &mut BTreeMap<TokenId, FieldPlayer>
     ) {
         for (_position, field_player) in field_players {
             if field_player.teamwork.is_none() {
@@ -240,14 +240,14 @@ impl FiveIds {
 }
 
 impl FiveIds {
-    pub fn reduce_morale(&self, field_players: &mut HashMap<TokenId, FieldPlayer>) {
+    pub fn reduce_morale(&self, field_players: &mut BTreeMap<TokenId, FieldPlayer>) {
         for (_player_position, player_id) in &self.field_players {
             let player = field_players.get_mut(player_id).unwrap();
             player.stats.morale -= 3;
         }
     }
 
-    pub fn increase_morale(&self, field_players: &mut HashMap<TokenId, FieldPlayer>) {
+    pub fn increase_morale(&self, field_players: &mut BTreeMap<TokenId, FieldPlayer>) {
         for (_player_position, player_id) in &self.field_players {
             let player = field_players.get_mut(player_id).unwrap();
             player.stats.morale += 2;
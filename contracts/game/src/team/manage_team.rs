@@ -1,5 +1,6 @@
 use crate::*;
 use crate::game::actions::action::ActionTypes;
+use crate::game::autopilot::AutopilotPolicy;
 use crate::team::five::{IceTimePriority, Tactics};
 use crate::team::numbers::*;
 use crate::team::players::player::GoalieSubstitution;
@@ -12,13 +13,13 @@ impl Hockey {
 
         if game.user1.account_id == account_id {
             if !game.user1.take_to_called {
-                self.change_stats_take_to(&mut game.user1, &mut game.user2);
+                game.user1.change_stats_take_to(&mut game.user2);
             } else {
                 panic!("You have already used TO")
             }
         } else if game.user2.account_id == account_id {
             if !game.user2.take_to_called {
-                self.change_stats_take_to(&mut game.user2, &mut game.user1);
+                game.user2.change_stats_take_to(&mut game.user1);
             } else {
                 panic!("You have already used TO")
             }
@@ -31,7 +32,8 @@ impl Hockey {
             account_id,
         }]);
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &event);
 
         event
     }
@@ -42,7 +44,7 @@ impl Hockey {
 
         let event = if game.user1.account_id == account_id {
             if !game.user1.coach_speech_called {
-                self.change_stats_coach_speech(&mut game.user1);
+                game.user1.change_stats_coach_speech();
                 game.generate_event(&mut vec![CoachSpeech {
                     action_type: ActionTypes::CoachSpeech,
                     account_id
@@ -52,7 +54,7 @@ impl Hockey {
             }
         } else if game.user2.account_id == account_id {
             if !game.user2.coach_speech_called {
-                self.change_stats_coach_speech(&mut game.user2);
+                game.user2.change_stats_coach_speech();
                 game.generate_event(&mut vec![CoachSpeech {
                     action_type: ActionTypes::CoachSpeech,
                     account_id
@@ -64,7 +66,8 @@ impl Hockey {
             panic!("The account is not a member of the game")
         };
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &event);
 
         event
     }
@@ -74,17 +77,13 @@ impl Hockey {
         let mut game: Game = self.internal_get_game(&game_id).into();
 
         let event = if game.user1.account_id == account_id && !game.user1.is_goalie_out {
-            game.user1.is_goalie_out = true;
-            game.user1.team.active_goalie_substitution = goalie_substitution;
-            game.user1.team.goalie_out();
+            game.user1.goalie_out(goalie_substitution);
             game.generate_event(&mut vec![GoalieOut {
                 action_type: ActionTypes::GoalieOut,
                 account_id
             }])
         } else if game.user2.account_id == account_id && !game.user2.is_goalie_out {
-            game.user2.is_goalie_out = true;
-            game.user2.team.active_goalie_substitution = goalie_substitution;
-            game.user2.team.goalie_out();
+            game.user2.goalie_out(goalie_substitution);
             game.generate_event(&mut vec![GoalieOut {
                 action_type: ActionTypes::GoalieOut,
                 account_id
@@ -93,7 +92,8 @@ impl Hockey {
             panic!("Impossible to remove the goalkeeper")
         };
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &event);
 
         event
     }
@@ -103,15 +103,13 @@ impl Hockey {
         let mut game: Game = self.internal_get_game(&game_id).into();
 
         let event = if game.user1.account_id == account_id  && game.user1.is_goalie_out{
-            game.user1.is_goalie_out = false;
-            game.user1.team.goalie_out();
+            game.user1.goalie_back();
             game.generate_event(&mut vec![GoalieBack {
                 action_type: ActionTypes::GoalieBack,
                 account_id
             }])
         } else if game.user2.account_id == account_id && game.user2.is_goalie_out{
-            game.user2.is_goalie_out = false;
-            game.user2.team.goalie_out();
+            game.user2.goalie_back();
             game.generate_event(&mut vec![GoalieBack {
                 action_type: ActionTypes::GoalieBack,
                 account_id
@@ -120,7 +118,8 @@ impl Hockey {
             panic!("Impossible to return the goalkeeper")
         };
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
+        self.internal_save_event(game_id, &event);
 
         event
     }
@@ -133,7 +132,7 @@ impl Hockey {
         let five = user.team.fives.get_mut(&five_number).unwrap();
         five.tactic = tactic;
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
     }
 
     pub fn change_ice_priority(&mut self, ice_time_priority: IceTimePriority, five: FiveNumber, game_id: GameId) {
@@ -146,7 +145,7 @@ impl Hockey {
             game.user2.team.fives.get_mut(&five).unwrap().ice_time_priority = ice_time_priority;
         }
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
     }
 
     pub fn change_positions(&mut self, number_five: FiveNumber, game_id: GameId, position1: PlayerPosition, position2: PlayerPosition) {
@@ -159,63 +158,111 @@ impl Hockey {
             self.swap_positions(&mut game.user2, number_five, position1, position2);
         }
 
-        self.games.insert(&game_id, &game);
+        self.internal_save_game(game);
+    }
+
+    /// Order of shooters if the game goes to a shootout
+    pub fn set_shootout_shooters(&mut self, game_id: GameId, shooters: Vec<TokenId>) {
+        let account_id = predecessor_account_id();
+        let mut game: Game = self.internal_get_game(&game_id);
+
+        let user = game.get_user_info_by_acc_id(&account_id);
+        for player_id in &shooters {
+            if !user.team.field_players.contains_key(player_id) {
+                panic!("Player not found: {}", player_id);
+            }
+        }
+        user.shootout_shooters = shooters;
+
+        self.internal_save_game(game);
+    }
+
+    /// Used in the games created afterwards. No policy removes it
+    pub fn set_autopilot_policy(&mut self, policy: Option<AutopilotPolicy>) {
+        let account_id = predecessor_account_id();
+
+        match policy {
+            Some(policy) => {
+                policy.assert_valid();
+                self.autopilot_policies.insert(&account_id, &policy);
+            },
+            None => {
+                self.autopilot_policies.remove(&account_id);
+            }
+        }
+    }
+
+    pub fn get_autopilot_policy(&self, account_id: AccountId) -> Option<AutopilotPolicy> {
+        self.autopilot_policies.get(&account_id)
     }
 }
 
-impl Hockey {
-    fn change_stats_take_to(&self, user1: &mut UserInfo, user2: &mut UserInfo) {
-        for (_five_number, five_ids) in user1.team.fives.clone() {
+impl UserInfo {
+    pub(crate) fn change_stats_take_to(&mut self, opponent: &mut UserInfo) {
+        for (_five_number, five_ids) in self.team.fives.clone() {
             for (_player_pos, field_player) in five_ids.field_players {
                 if field_player == "" {
                     continue;
                 }
-                let field_player = user1.team.get_field_player_mut(&field_player);
+                let field_player = self.team.get_field_player_mut(&field_player);
                 field_player.stats.increase_strength(5);
                 field_player.stats.increase_iq(3)
             }
         }
 
-        for (_goalie_number, goalie) in user1.team.goalies.iter_mut() {
+        for (_goalie_number, goalie) in self.team.goalies.iter_mut() {
             goalie.stats.increase_strength(5);
         }
 
-        for (_five_number, five_ids) in user2.team.fives.clone() {
+        for (_five_number, five_ids) in opponent.team.fives.clone() {
             for (_player_pos, field_player) in five_ids.field_players {
                 if field_player == "" {
                     continue;
                 }
-                let field_player = user2.team.get_field_player_mut(&field_player);
+                let field_player = opponent.team.get_field_player_mut(&field_player);
                 field_player.stats.increase_strength(3);
                 field_player.stats.morale += 3;
             }
         }
 
-        for (_goalie_number, goalie) in user1.team.goalies.iter_mut() {
+        for (_goalie_number, goalie) in self.team.goalies.iter_mut() {
             goalie.stats.increase_strength(3);
         }
 
-        user1.take_to_called = true;
+        self.take_to_called = true;
     }
 
-    fn change_stats_coach_speech(&self, user: &mut UserInfo) {
-        for (_five_number, five_ids) in user.team.fives.clone() {
+    pub(crate) fn change_stats_coach_speech(&mut self) {
+        for (_five_number, five_ids) in self.team.fives.clone() {
             for (_player_pos, field_player) in five_ids.field_players {
                 if field_player == "" {
                     continue;
                 }
-                let field_player = user.team.get_field_player_mut(&field_player);
+                let field_player = self.team.get_field_player_mut(&field_player);
                 field_player.stats.increase_strength(5);
             }
         }
 
-        for (_goalie_number, goalie) in user.team.goalies.iter_mut() {
+        for (_goalie_number, goalie) in self.team.goalies.iter_mut() {
             goalie.stats.increase_strength(5);
         }
 
-        user.coach_speech_called = true;
+        self.coach_speech_called = true;
     }
 
+    pub(crate) fn goalie_out(&mut self, goalie_substitution: GoalieSubstitution) {
+        self.is_goalie_out = true;
+        self.team.active_goalie_substitution = goalie_substitution;
+        self.team.goalie_out();
+    }
+
+    pub(crate) fn goalie_back(&mut self) {
+        self.is_goalie_out = false;
+        self.team.goalie_back();
+    }
+}
+
+impl Hockey {
     fn swap_positions(&mut self, user_info: &mut UserInfo, number_five: FiveNumber, position1: PlayerPosition, position2: PlayerPosition) {
         let five = user_info.team.fives.get_mut(&number_five).unwrap();
         let first_player_id = five.field_players.get(&position1).unwrap().clone();
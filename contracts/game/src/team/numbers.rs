@@ -3,7 +3,7 @@ use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(BorshDeserialize, BorshSerialize)]
 #[derive(Serialize, Deserialize, Debug)]
-#[derive(PartialEq, Eq, PartialOrd, Hash, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum GoalieNumber {
     MainGoalkeeper,
@@ -12,7 +12,7 @@ pub enum GoalieNumber {
 
 #[derive(BorshDeserialize, BorshSerialize)]
 #[derive(Serialize, Deserialize, Debug)]
-#[derive(PartialEq, Eq, Hash, PartialOrd, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
 #[serde(crate = "near_sdk::serde")]
 pub enum FiveNumber {
     First,
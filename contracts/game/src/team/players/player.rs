@@ -90,7 +90,7 @@ impl fmt::Display for PlayerPosition {
 
 
 #[derive(PartialEq, Clone, Copy, BorshDeserialize, BorshSerialize)]
-#[derive(Eq, Hash)]
+#[derive(Eq, Hash, PartialOrd, Ord)]
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub enum PlayerRole {
@@ -1,7 +1,7 @@
 use crate::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use crate::{PlayerPosition};
 use crate::PlayerPosition::{AdditionalPosition, LeftWing, RightWing};
 use crate::team::five::{ActiveFive, FiveIds, IceTimePriority};
@@ -25,19 +25,19 @@ const SUPER_HIGH_PRIORITY: u8 = 12;
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Team {
-    pub(crate) fives: HashMap<FiveNumber, FiveIds>,
+    pub(crate) fives: BTreeMap<FiveNumber, FiveIds>,
     pub(crate) active_five: ActiveFive,
 
-    pub(crate) field_players: HashMap<TokenId, FieldPlayer>,
+    pub(crate) field_players: BTreeMap<TokenId, FieldPlayer>,
 
     pub(crate) penalty_players: Vec<TokenId>,
     pub(crate) players_to_big_penalty: Vec<TokenId>,
     pub(crate) players_to_small_penalty: Vec<TokenId>,
 
-    pub(crate) goalie_substitutions: HashMap<GoalieSubstitution, TokenId>,
+    pub(crate) goalie_substitutions: BTreeMap<GoalieSubstitution, TokenId>,
     pub(crate) active_goalie_substitution: GoalieSubstitution,
 
-    pub(crate) goalies: HashMap<GoalieNumber, Goalie>,
+    pub(crate) goalies: BTreeMap<GoalieNumber, Goalie>,
     pub(crate) active_goalie: GoalieNumber,
 
     pub(crate) score: u8,
@@ -518,7 +518,7 @@ impl Team {
         }
     }
 
-    fn get_players_in_five(&self, number: &FiveNumber) -> HashMap<PlayerPosition, TokenId> {
+    fn get_players_in_five(&self, number: &FiveNumber) -> BTreeMap<PlayerPosition, TokenId> {
         self.get_five(number).field_players.clone()
     }
 
@@ -560,6 +560,34 @@ impl Team {
         return true;
     }
 
+    /// 3-on-3 overtime: the regular fives keep only the center and both defenders.
+    /// Penalties from regulation expire
+    pub fn set_overtime_fives(&mut self) {
+        for player_id in &self.penalty_players {
+            self.field_players.get_mut(player_id).unwrap().number_of_penalty_events = Some(0);
+        }
+        self.penalty_players.clear();
+        self.players_to_big_penalty.clear();
+        self.players_to_small_penalty.clear();
+
+        for number in &[First, Second, Third, Fourth] {
+            let five = self.get_five_mut(number);
+            five.field_players.remove(&LeftWing);
+            five.field_players.remove(&RightWing);
+        }
+
+        let first_five = self.get_five(&First).field_players.clone();
+        let active_five = self.get_active_five_mut();
+        active_five.current_number = First;
+        active_five.field_players = first_five;
+        active_five.replaced_position.clear();
+        active_five.time_field = Some(0);
+
+        if active_five.is_goalie_out {
+            self.goalie_out();
+        }
+    }
+
     pub fn goalie_back(&mut self) {
         let active_five = self.get_active_five_mut();
         active_five.is_goalie_out = false;
@@ -1,6 +1,6 @@
 use crate::*;
 use near_sdk::serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use near_sdk::serde_json;
 use crate::PlayerPosition::{Center, LeftDefender, LeftWing, RightDefender, RightWing};
 use crate::team::players::goalie::{Goalie};
@@ -12,17 +12,17 @@ use crate::team::team::Team;
 
 
 #[derive(BorshDeserialize, BorshSerialize)]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TeamMetadata {
-    pub(crate) fives: HashMap<FiveNumber, FiveIds>,
-    pub(crate) goalies: HashMap<GoalieNumber, PlayerMetadata>,
-    pub(crate) goalie_substitutions: HashMap<GoalieSubstitution, TokenId>,
-    pub(crate) field_players_metadata: HashMap<TokenId, PlayerMetadata>,
+    pub(crate) fives: BTreeMap<FiveNumber, FiveIds>,
+    pub(crate) goalies: BTreeMap<GoalieNumber, PlayerMetadata>,
+    pub(crate) goalie_substitutions: BTreeMap<GoalieSubstitution, TokenId>,
+    pub(crate) field_players_metadata: BTreeMap<TokenId, PlayerMetadata>,
 }
 
 pub fn team_metadata_to_team(team_metadata: TeamMetadata, user_id: usize) -> Team {
-    let mut fives: HashMap<FiveNumber, FiveIds> = HashMap::new();
+    let mut fives: BTreeMap<FiveNumber, FiveIds> = BTreeMap::new();
     let mut field_players = to_field_players(&team_metadata.field_players_metadata, &user_id);
 
     for (number, five_ids) in team_metadata.fives {
@@ -30,7 +30,7 @@ pub fn team_metadata_to_team(team_metadata: TeamMetadata, user_id: usize) -> Tea
         fives.insert(number, five_ids);
     }
 
-    let mut goalies = HashMap::new();
+    let mut goalies = BTreeMap::new();
     for (number, goalie) in team_metadata.goalies {
         goalies.insert(number, to_goalie(goalie, user_id));
     }
@@ -66,8 +66,8 @@ pub fn team_metadata_to_team(team_metadata: TeamMetadata, user_id: usize) -> Tea
     team
 }
 
-fn to_field_players(field_players_metadata: &HashMap<TokenId, PlayerMetadata>, user_id: &usize) -> HashMap<TokenId, FieldPlayer> {
-    let mut result: HashMap<TokenId, FieldPlayer> = HashMap::new();
+fn to_field_players(field_players_metadata: &BTreeMap<TokenId, PlayerMetadata>, user_id: &usize) -> BTreeMap<TokenId, FieldPlayer> {
+    let mut result: BTreeMap<TokenId, FieldPlayer> = BTreeMap::new();
     for (token_id, field_player_metadata) in field_players_metadata {
         let mut field_player = to_field_player((*field_player_metadata).clone(), user_id);
         field_player.id = Some(token_id.clone());
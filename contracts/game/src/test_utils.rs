@@ -0,0 +1,120 @@
+use near_sdk::{AccountId, testing_env};
+use near_sdk::serde_json::{self, json, Map, Value};
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use crate::{GameId, NEAR};
+use crate::game::game::Game;
+use crate::game::match_rules::MatchRules;
+use crate::game::rng::{Rng, SeededRng};
+use crate::manager::TokenBalance;
+use crate::team::team_metadata::TeamMetadata;
+
+const FIELD_PLAYER_STATS: [&str; 26] = [
+    "acceleration", "agility", "balance", "endurance", "speed",
+    "slap_shot_accuracy", "slap_shot_power", "wrist_shot_accuracy", "wrist_shot_power",
+    "deking", "hand_eye", "passing", "puck_control",
+    "aggressiveness", "body_checking", "durability", "fighting_skill", "strength",
+    "discipline", "offensive", "poise", "morale",
+    "defensive_awareness", "face_offs", "shot_blocking", "stick_checking",
+];
+
+const GOALIE_STATS: [&str; 20] = [
+    "angles", "breakaway", "five_hole", "glove_side_high", "glove_side_low", "stick_side_high", "stick_side_low",
+    "passing", "poise", "poke_check", "puck_playing", "rebound_control", "recover",
+    "aggressiveness", "agility", "durability", "endurance", "speed", "vision", "morale",
+];
+
+/// The contract is `accounts(0)`
+pub fn set_context(predecessor_account_id: AccountId) {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(accounts(0))
+        .predecessor_account_id(predecessor_account_id)
+        .build());
+}
+
+fn get_stats(names: &[&str]) -> Value {
+    Value::Object(names.iter().map(|name| (name.to_string(), json!(50))).collect::<Map<String, Value>>())
+}
+
+fn get_player_metadata(extra: Value) -> Value {
+    json!({
+        "title": "Player",
+        "description": null,
+        "media": "img",
+        "media_hash": null,
+        "issued_at": null,
+        "expires_at": null,
+        "starts_at": null,
+        "updated_at": null,
+        "extra": extra.to_string(),
+    })
+}
+
+/// Only the first five and the main goalie
+pub fn get_team_metadata(prefix: &str) -> TeamMetadata {
+    let positions = ["Center", "LeftWing", "RightWing", "LeftDefender", "RightDefender"];
+
+    let mut field_players = Map::new();
+    let mut five = Map::new();
+    for (number, position) in positions.iter().enumerate() {
+        let token_id = format!("{}-{}", prefix, number);
+        field_players.insert(token_id.clone(), get_player_metadata(json!({
+            "reality": false,
+            "nationality": "CA",
+            "birthday": 0,
+            "player_type": "FieldPlayer",
+            "number": number,
+            "hand": "L",
+            "player_role": if position.ends_with("Defender") { "TwoWay" } else { "Playmaker" },
+            "native_position": position,
+            "stats": get_stats(&FIELD_PLAYER_STATS),
+        })));
+        five.insert(position.to_string(), json!(token_id));
+    }
+
+    let goalie = get_player_metadata(json!({
+        "reality": false,
+        "nationality": "CA",
+        "birthday": 0,
+        "player_type": "Goalie",
+        "number": 30,
+        "hand": "L",
+        "player_role": "Hybrid",
+        "stats": get_stats(&GOALIE_STATS),
+    }));
+
+    serde_json::from_value(json!({
+        "fives": {
+            "First": {
+                "field_players": five,
+                "number": "First",
+                "ice_time_priority": "Normal",
+                "tactic": "Neutral",
+            },
+        },
+        "goalies": { "MainGoalkeeper": goalie },
+        "goalie_substitutions": {},
+        "field_players_metadata": field_players,
+    })).unwrap()
+}
+
+pub fn get_reward(balance: u128) -> TokenBalance {
+    TokenBalance {
+        token_id: Some(NEAR.into()),
+        balance,
+    }
+}
+
+/// `accounts(1)` against `accounts(2)` with a seeded random source
+pub fn get_game_with_teams(game_id: GameId, teams: (TeamMetadata, TeamMetadata), rules: MatchRules) -> Game {
+    Game::new(teams,
+              accounts(1),
+              accounts(2),
+              get_reward(0),
+              &game_id,
+              Rng::Seeded(SeededRng::new(game_id.to_le_bytes().to_vec())),
+              rules)
+}
+
+pub fn get_game(game_id: GameId) -> Game {
+    get_game_with_teams(game_id, (get_team_metadata("a"), get_team_metadata("b")), MatchRules::default())
+}
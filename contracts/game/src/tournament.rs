@@ -0,0 +1,558 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::Timestamp;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::owner::MAX_BPS;
+
+pub type TournamentId = u64;
+
+const MAX_CAPACITY: u32 = 64;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TournamentStatus {
+    Registration,
+    Running,
+    Finished,
+    Cancelled,
+}
+
+/// A match of the bracket. An empty slot in the first round is a bye
+#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BracketMatch {
+    pub(crate) user1: Option<AccountId>,
+    pub(crate) user2: Option<AccountId>,
+    pub(crate) game_id: Option<GameId>,
+    pub(crate) winner_id: Option<AccountId>,
+}
+
+impl BracketMatch {
+    fn new(user1: Option<AccountId>, user2: Option<AccountId>) -> BracketMatch {
+        BracketMatch {
+            user1,
+            user2,
+            game_id: None,
+            winner_id: None,
+        }
+    }
+
+    fn get_loser_id(&self) -> Option<AccountId> {
+        match self.winner_id {
+            Some(ref winner_id) if self.user1.as_ref() == Some(winner_id) => self.user2.clone(),
+            Some(_) => self.user1.clone(),
+            None => None,
+        }
+    }
+}
+
+/// Position of a game in the bracket
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BracketGame {
+    pub(crate) tournament_id: TournamentId,
+    pub(crate) round: usize,
+    pub(crate) index: usize,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Tournament {
+    pub(crate) tournament_id: TournamentId,
+    pub(crate) creator_id: AccountId,
+    pub(crate) entry_fee: Balance,
+    pub(crate) capacity: u32,
+    pub(crate) start_time: Timestamp,
+    // Shares of the prize pool in basis points: the winner, the finalist, each semifinalist and so on
+    pub(crate) prize_shares: Vec<u16>,
+    pub(crate) rules: VMatchRules,
+    // In the order of registration
+    pub(crate) participants: Vec<AccountId>,
+    pub(crate) rounds: Vec<Vec<BracketMatch>>,
+    pub(crate) status: TournamentStatus,
+}
+
+impl Tournament {
+    fn get_prize_pool(&self) -> Balance {
+        self.entry_fee * self.participants.len() as Balance
+    }
+
+    fn assert_status(&self, status: TournamentStatus) {
+        assert_eq!(self.status, status, "Wrong tournament status");
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VTournament {
+    Current(Tournament),
+}
+
+impl From<VTournament> for Tournament {
+    fn from(v_tournament: VTournament) -> Self {
+        match v_tournament {
+            VTournament::Current(tournament) => tournament,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TournamentOutput {
+    tournament_id: TournamentId,
+    creator_id: AccountId,
+    entry_fee: U128,
+    capacity: u32,
+    start_time: Timestamp,
+    prize_shares: Vec<u16>,
+    prize_pool: U128,
+    rules: MatchRules,
+    participants: Vec<AccountId>,
+    rounds: Vec<Vec<BracketMatch>>,
+    status: TournamentStatus,
+}
+
+impl From<Tournament> for TournamentOutput {
+    fn from(tournament: Tournament) -> Self {
+        TournamentOutput {
+            tournament_id: tournament.tournament_id,
+            creator_id: tournament.creator_id.clone(),
+            entry_fee: U128::from(tournament.entry_fee),
+            capacity: tournament.capacity,
+            start_time: tournament.start_time,
+            prize_shares: tournament.prize_shares.clone(),
+            prize_pool: U128::from(tournament.get_prize_pool()),
+            rules: tournament.rules.clone().into(),
+            participants: tournament.participants,
+            rounds: tournament.rounds,
+            status: tournament.status,
+        }
+    }
+}
+
+/// Seeds in the order of the first round slots, so the top seeds meet as late as possible
+fn get_seed_order(bracket_size: usize) -> Vec<usize> {
+    let mut seeds = vec![1];
+    while seeds.len() < bracket_size {
+        let size = seeds.len() * 2;
+        seeds = seeds.iter()
+            .flat_map(|seed| vec![*seed, size + 1 - seed])
+            .collect();
+    }
+
+    seeds
+}
+
+/// Each share after the second one is paid to twice as many players
+fn assert_valid_prize_shares(prize_shares: &[u16], capacity: u32) {
+    let number_of_rounds = capacity.trailing_zeros() as usize;
+    assert!(!prize_shares.is_empty() && prize_shares.len() <= number_of_rounds + 1,
+            "Expected from 1 to {} prize shares", number_of_rounds + 1);
+
+    let total: u64 = prize_shares.iter()
+        .enumerate()
+        .map(|(place, share)| *share as u64 * (1u64 << place.saturating_sub(1)))
+        .sum();
+    assert_eq!(total, MAX_BPS as u64, "Prize shares must add up to {} basis points", MAX_BPS);
+}
+
+impl Hockey {
+    pub(crate) fn internal_get_tournament(&self, tournament_id: &TournamentId) -> Tournament {
+        self.tournaments.get(tournament_id).expect("Tournament not found").into()
+    }
+
+    pub(crate) fn internal_save_tournament(&mut self, tournament: Tournament) {
+        self.tournaments.insert(&tournament.tournament_id.clone(), &VTournament::Current(tournament));
+    }
+
+    /// Participants are seeded by rating. Top seeds get byes if the bracket is not full
+    fn internal_build_bracket(&self, tournament: &mut Tournament) {
+        let mut seeded = tournament.participants.clone();
+        seeded.sort_by_key(|account_id| std::cmp::Reverse(self.internal_get_stats(account_id).rating));
+
+        let bracket_size = seeded.len().next_power_of_two();
+        let slots: Vec<Option<AccountId>> = get_seed_order(bracket_size).iter()
+            .map(|seed| seeded.get(seed - 1).cloned())
+            .collect();
+
+        let mut rounds = vec![slots.chunks(2)
+            .map(|pair| BracketMatch::new(pair[0].clone(), pair[1].clone()))
+            .collect::<Vec<BracketMatch>>()];
+
+        let mut number_of_matches = bracket_size / 4;
+        while number_of_matches > 0 {
+            rounds.push((0..number_of_matches).map(|_| BracketMatch::new(None, None)).collect());
+            number_of_matches /= 2;
+        }
+
+        tournament.rounds = rounds;
+    }
+
+    fn internal_start_bracket_game(&mut self, tournament: &mut Tournament, round: usize, index: usize) {
+        let tournament_id = tournament.tournament_id;
+        let bracket_match = &tournament.rounds[round][index];
+        let user1 = bracket_match.user1.clone().expect("Bracket match is not ready");
+        let user2 = bracket_match.user2.clone().expect("Bracket match is not ready");
+
+        let team1 = self.tournament_teams.get(&(tournament_id, user1.clone())).expect("Team not found");
+        let team2 = self.tournament_teams.get(&(tournament_id, user2.clone())).expect("Team not found");
+
+        // The prize pool is paid when the tournament is finished
+        let reward = TokenBalance {
+            token_id: Some(NEAR.into()),
+            balance: 0,
+        };
+
+        let game = self.internal_create_game(user1, user2, reward, tournament.rules.clone().into(), (team1, team2), false);
+
+        self.tournament_games.insert(&game.game_id, &BracketGame {
+            tournament_id,
+            round,
+            index,
+        });
+        tournament.rounds[round][index].game_id = Some(game.game_id);
+    }
+
+    fn internal_set_bracket_winner(&mut self, tournament: &mut Tournament, round: usize, index: usize, winner_id: AccountId) {
+        let tournament_id = tournament.tournament_id;
+        let bracket_match = &mut tournament.rounds[round][index];
+        bracket_match.winner_id = Some(winner_id.clone());
+        if let Some(loser_id) = bracket_match.get_loser_id() {
+            self.tournament_teams.remove(&(tournament_id, loser_id));
+        }
+
+        if round + 1 == tournament.rounds.len() {
+            self.internal_finish_tournament(tournament, winner_id);
+            return;
+        }
+
+        let next_match = &mut tournament.rounds[round + 1][index / 2];
+        if index % 2 == 0 {
+            next_match.user1 = Some(winner_id);
+        } else {
+            next_match.user2 = Some(winner_id);
+        }
+
+        if next_match.user1.is_some() && next_match.user2.is_some() {
+            self.internal_start_bracket_game(tournament, round + 1, index / 2);
+        }
+    }
+
+    /// Called when a bracket game is over
+    pub(crate) fn internal_advance_tournament(&mut self, bracket_game: BracketGame, winner_id: AccountId) {
+        let mut tournament = self.internal_get_tournament(&bracket_game.tournament_id);
+        self.internal_set_bracket_winner(&mut tournament, bracket_game.round, bracket_game.index, winner_id);
+        self.internal_save_tournament(tournament);
+    }
+
+    /// Called when a bracket game is aborted
+    pub(crate) fn internal_replay_bracket_game(&mut self, bracket_game: BracketGame) {
+        let mut tournament = self.internal_get_tournament(&bracket_game.tournament_id);
+        self.internal_start_bracket_game(&mut tournament, bracket_game.round, bracket_game.index);
+        self.internal_save_tournament(tournament);
+    }
+
+    /// Pays out the prize pool. Shares of the places nobody took go to the winner
+    fn internal_finish_tournament(&mut self, tournament: &mut Tournament, winner_id: AccountId) {
+        tournament.status = TournamentStatus::Finished;
+        self.tournament_teams.remove(&(tournament.tournament_id, winner_id.clone()));
+
+        let prize_pool = tournament.get_prize_pool();
+        let fee = self.contract_config.get_fee(prize_pool);
+        self.service_fee += fee;
+        let prize_pool = prize_pool - fee;
+
+        let number_of_rounds = tournament.rounds.len();
+        let mut prizes = vec![];
+        for (round, matches) in tournament.rounds.iter().enumerate() {
+            let share = tournament.prize_shares.get(number_of_rounds - round).cloned().unwrap_or(0);
+            for loser_id in matches.iter().filter_map(|bracket_match| bracket_match.get_loser_id()) {
+                prizes.push((loser_id, prize_pool * share as Balance / MAX_BPS as Balance));
+            }
+        }
+        let paid: Balance = prizes.iter().map(|(_, prize)| prize).sum();
+        prizes.push((winner_id, prize_pool - paid));
+
+        for (account_id, prize) in prizes.into_iter().filter(|(_, prize)| *prize > 0) {
+            log!("Tournament {} prize for {} is {}", tournament.tournament_id, account_id, prize);
+            self.internal_update_stats(&account_id, UpdateStatsAction::AddTotalReward, None, Some(prize));
+            self.internal_credit(&account_id, &None, prize);
+        }
+    }
+
+    fn internal_cancel_tournament(&mut self, tournament: &mut Tournament) {
+        tournament.status = TournamentStatus::Cancelled;
+
+        for account_id in tournament.participants.iter() {
+            self.tournament_teams.remove(&(tournament.tournament_id, account_id.clone()));
+            self.internal_credit(account_id, &None, tournament.entry_fee);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Hockey {
+    /// Capacity must be a power of two. Full-length matches if rules are not set
+    pub fn create_tournament(
+        &mut self,
+        entry_fee: U128,
+        capacity: u32,
+        start_time: Timestamp,
+        prize_shares: Vec<u16>,
+        rules: Option<VMatchRules>
+    ) -> TournamentId {
+        self.assert_not_paused();
+        assert!((2..=MAX_CAPACITY).contains(&capacity) && capacity.is_power_of_two(),
+                "Capacity must be a power of two from 2 to {}", MAX_CAPACITY);
+        assert!(start_time > env::block_timestamp(), "Start time has already passed");
+        assert_valid_prize_shares(&prize_shares, capacity);
+
+        let rules = rules.unwrap_or_else(|| VMatchRules::Current(MatchRules::default()));
+        MatchRules::from(rules.clone()).assert_valid();
+
+        let tournament_id = self.next_tournament_id;
+        self.internal_save_tournament(Tournament {
+            tournament_id,
+            creator_id: predecessor_account_id(),
+            entry_fee: entry_fee.0,
+            capacity,
+            start_time,
+            prize_shares,
+            rules,
+            participants: vec![],
+            rounds: vec![],
+            status: TournamentStatus::Registration,
+        });
+        self.next_tournament_id += 1;
+
+        tournament_id
+    }
+
+    /// The attached deposit must be equal to the entry fee. The team is taken when registering
+    #[payable]
+    pub fn register_for_tournament(&mut self, tournament_id: TournamentId) -> Promise {
+        self.assert_not_paused();
+        let account_id = predecessor_account_id();
+        let deposit = env::attached_deposit();
+
+        let tournament = self.internal_get_tournament(&tournament_id);
+        tournament.assert_status(TournamentStatus::Registration);
+        assert_eq!(deposit, tournament.entry_fee, "Deposit must be equal to the entry fee");
+        assert!(!tournament.participants.contains(&account_id), "Already registered");
+        assert!((tournament.participants.len() as u32) < tournament.capacity, "The tournament is full");
+
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
+            .with_static_gas(Gas(100_000_000_000_000))
+            .get_owner_team(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(100_000_000_000_000))
+                    .on_get_tournament_team(tournament_id, account_id, deposit)
+            )
+    }
+
+    #[private]
+    pub fn on_get_tournament_team(&mut self,
+                                  tournament_id: TournamentId,
+                                  account_id: AccountId,
+                                  deposit: Balance,
+                                  #[callback_result] call_result: Result<TeamMetadata, PromiseError>
+    ) -> bool {
+        if call_result.is_err() {
+            self.internal_credit(&account_id, &None, deposit);
+            log!("The team is incomplete");
+            return false;
+        }
+
+        let mut tournament = self.internal_get_tournament(&tournament_id);
+        // The tournament could change while the team was requested
+        if tournament.status != TournamentStatus::Registration
+            || tournament.participants.contains(&account_id)
+            || tournament.participants.len() as u32 >= tournament.capacity {
+            self.internal_credit(&account_id, &None, deposit);
+            log!("Registration is closed");
+            return false;
+        }
+
+        self.tournament_teams.insert(&(tournament_id, account_id.clone()), &call_result.unwrap());
+        tournament.participants.push(account_id);
+        self.internal_save_tournament(tournament);
+
+        true
+    }
+
+    /// Can be called by anyone after the start time. Cancelled if less than 2 players registered
+    pub fn start_tournament(&mut self, tournament_id: TournamentId) -> TournamentStatus {
+        let mut tournament = self.internal_get_tournament(&tournament_id);
+        tournament.assert_status(TournamentStatus::Registration);
+        assert!(env::block_timestamp() >= tournament.start_time, "The tournament has not started yet");
+
+        if tournament.participants.len() < 2 {
+            self.internal_cancel_tournament(&mut tournament);
+        } else {
+            tournament.status = TournamentStatus::Running;
+            self.internal_build_bracket(&mut tournament);
+
+            for index in 0..tournament.rounds[0].len() {
+                let bracket_match = &tournament.rounds[0][index];
+                match (bracket_match.user1.clone(), bracket_match.user2.clone()) {
+                    (Some(_), Some(_)) => self.internal_start_bracket_game(&mut tournament, 0, index),
+                    (Some(account_id), None) | (None, Some(account_id)) =>
+                        self.internal_set_bracket_winner(&mut tournament, 0, index, account_id),
+                    (None, None) => {},
+                }
+            }
+        }
+
+        let status = tournament.status.clone();
+        self.internal_save_tournament(tournament);
+
+        status
+    }
+
+    /// Entry fees are refunded. Only before the start by the creator or the owner
+    pub fn cancel_tournament(&mut self, tournament_id: TournamentId) {
+        let mut tournament = self.internal_get_tournament(&tournament_id);
+        let account_id = predecessor_account_id();
+        assert!(account_id == tournament.creator_id || account_id == self.owner_id,
+                "Only the creator or the owner can cancel the tournament");
+        tournament.assert_status(TournamentStatus::Registration);
+
+        self.internal_cancel_tournament(&mut tournament);
+        self.internal_save_tournament(tournament);
+    }
+
+    pub fn get_tournament(&self, tournament_id: TournamentId) -> TournamentOutput {
+        self.internal_get_tournament(&tournament_id).into()
+    }
+
+    pub fn get_tournaments(&self, from_index: u64, limit: u64) -> Vec<TournamentOutput> {
+        let values = self.tournaments.values_as_vector();
+        (from_index..std::cmp::min(from_index + limit, values.len()))
+            .map(|index| Tournament::from(values.get(index).unwrap()).into())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::manager::{Stats, VStats};
+    use crate::test_utils::{get_team_metadata, set_context};
+
+    const ENTRY_FEE: Balance = 100;
+    const START_TIME: Timestamp = 100 * SECOND;
+
+    fn set_context_at(timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    /// `accounts(1)`, `accounts(2)` and `accounts(3)`, from the highest rating to the lowest,
+    /// in a tournament for four players that has just started
+    fn get_started_tournament() -> (Hockey, TournamentId) {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let tournament_id = hockey.create_tournament(U128::from(ENTRY_FEE), 4, START_TIME, vec![6_000, 2_000, 1_000], None);
+
+        for (index, rating) in [1_600, 1_500, 1_400].iter().enumerate() {
+            let account_id = accounts(index + 1);
+            let mut stats = Stats::new(&account_id);
+            stats.rating = *rating;
+            hockey.stats.insert(&account_id, &VStats::Current(stats));
+
+            assert!(hockey.on_get_tournament_team(tournament_id, account_id.clone(), ENTRY_FEE, Ok(get_team_metadata(account_id.as_str()))));
+        }
+
+        set_context_at(START_TIME);
+        assert_eq!(hockey.start_tournament(tournament_id), TournamentStatus::Running);
+
+        (hockey, tournament_id)
+    }
+
+    fn finish_bracket_game(hockey: &mut Hockey, tournament_id: TournamentId, round: usize, index: usize, winner_index: UserId) {
+        let game_id = hockey.internal_get_tournament(&tournament_id).rounds[round][index].game_id.expect("Game not started");
+        let mut game = hockey.internal_get_game(&game_id);
+        hockey.internal_finish_game(&mut game, winner_index);
+    }
+
+    #[test]
+    fn top_seeds_meet_as_late_as_possible() {
+        assert_eq!(get_seed_order(2), vec![1, 2]);
+        assert_eq!(get_seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn prize_shares_are_paid_per_place() {
+        assert_valid_prize_shares(&[10_000], 2);
+        assert_valid_prize_shares(&[6_000, 2_000, 1_000], 4);
+        assert_valid_prize_shares(&[4_000, 2_000, 1_000, 500], 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Prize shares must add up to 10000 basis points")]
+    fn prize_shares_without_semifinalists_twice() {
+        assert_valid_prize_shares(&[6_000, 2_000, 2_000], 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected from 1 to 2 prize shares")]
+    fn more_prize_shares_than_places() {
+        assert_valid_prize_shares(&[6_000, 2_000, 1_000], 2);
+    }
+
+    #[test]
+    fn top_seed_gets_a_bye() {
+        let (hockey, tournament_id) = get_started_tournament();
+
+        let rounds = hockey.internal_get_tournament(&tournament_id).rounds;
+        assert_eq!(rounds.len(), 2);
+
+        let bye = &rounds[0][0];
+        assert_eq!((bye.user1.clone(), bye.user2.clone()), (Some(accounts(1)), None));
+        assert_eq!(bye.winner_id, Some(accounts(1)));
+        assert!(bye.game_id.is_none());
+
+        let semifinal = &rounds[0][1];
+        assert_eq!((semifinal.user1.clone(), semifinal.user2.clone()), (Some(accounts(2)), Some(accounts(3))));
+        assert!(semifinal.game_id.is_some());
+
+        assert_eq!(rounds[1][0].user1, Some(accounts(1)));
+        assert!(rounds[1][0].game_id.is_none());
+    }
+
+    #[test]
+    fn winners_advance_and_share_the_prize_pool() {
+        let (mut hockey, tournament_id) = get_started_tournament();
+
+        finish_bracket_game(&mut hockey, tournament_id, 0, 1, 1);
+        let final_match = &hockey.internal_get_tournament(&tournament_id).rounds[1][0];
+        assert_eq!(final_match.user2, Some(accounts(2)));
+        assert!(final_match.game_id.is_some());
+
+        finish_bracket_game(&mut hockey, tournament_id, 1, 0, 1);
+        assert_eq!(hockey.internal_get_tournament(&tournament_id).status, TournamentStatus::Finished);
+
+        // 300 without the 10% fee. Nobody took the place of the second semifinalist
+        assert_eq!(hockey.service_fee, 30);
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(189))]);
+        assert_eq!(hockey.get_claimable(accounts(2)), vec![(None, U128::from(54))]);
+        assert_eq!(hockey.get_claimable(accounts(3)), vec![(None, U128::from(27))]);
+    }
+
+    #[test]
+    fn tournament_with_one_player_is_cancelled() {
+        set_context(accounts(0));
+        let mut hockey = Hockey::new(accounts(0));
+        let tournament_id = hockey.create_tournament(U128::from(ENTRY_FEE), 4, START_TIME, vec![10_000], None);
+        assert!(hockey.on_get_tournament_team(tournament_id, accounts(1), ENTRY_FEE, Ok(get_team_metadata("a"))));
+
+        set_context_at(START_TIME);
+        assert_eq!(hockey.start_tournament(tournament_id), TournamentStatus::Cancelled);
+        assert_eq!(hockey.get_claimable(accounts(1)), vec![(None, U128::from(ENTRY_FEE))]);
+    }
+}
@@ -6,6 +6,8 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedSet;
 use near_sdk::env::{attached_deposit, predecessor_account_id};
 use crate::{Hockey, StorageKey};
+use crate::game::autopilot::AutopilotPolicy;
+use crate::game::match_rules::{MatchRules, VMatchRules};
 use crate::team::team::Team;
 
 pub type UserId = usize;
@@ -22,6 +24,35 @@ pub struct UserInfo {
     pub(crate) take_to_called: bool,
     pub(crate) coach_speech_called: bool,
     pub(crate) is_goalie_out: bool,
+    pub(crate) shootout_shooters: Vec<TokenId>,
+    // Decisions made without the manager
+    pub(crate) autopilot: Option<AutopilotPolicy>,
+}
+
+/// Layout of `UserInfo` in the first version
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UserInfoV1 {
+    pub(crate) user_id: UserId,
+    pub(crate) team: Team,
+    pub(crate) account_id: AccountId,
+    pub(crate) take_to_called: bool,
+    pub(crate) coach_speech_called: bool,
+    pub(crate) is_goalie_out: bool,
+}
+
+impl From<UserInfoV1> for UserInfo {
+    fn from(user_info: UserInfoV1) -> Self {
+        UserInfo {
+            user_id: user_info.user_id,
+            team: user_info.team,
+            account_id: user_info.account_id,
+            take_to_called: user_info.take_to_called,
+            coach_speech_called: user_info.coach_speech_called,
+            is_goalie_out: user_info.is_goalie_out,
+            shootout_shooters: vec![],
+            autopilot: None,
+        }
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -34,12 +65,38 @@ pub struct Account {
     pub(crate) requests_play_received: UnorderedMap<AccountId, Balance>,
 }
 
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VAccount {
+    Current(Account),
+}
+
+impl From<VAccount> for Account {
+    fn from(v_account: VAccount) -> Self {
+        match v_account {
+            VAccount::Current(account) => account,
+        }
+    }
+}
+
+impl Hockey {
+    /// Accounts stored before versioning are read from the legacy map
+    pub(crate) fn internal_get_account(&self, account_id: &AccountId) -> Option<Account> {
+        self.accounts.get(account_id)
+            .map(|account| account.into())
+            .or_else(|| self.legacy_accounts.get(account_id))
+    }
+
+    pub(crate) fn internal_save_account(&mut self, account_id: &AccountId, account: Account) {
+        self.accounts.insert(account_id, &VAccount::Current(account));
+    }
+}
+
 #[near_bindgen]
 impl Hockey {
     pub fn register_account(&mut self) {
         let account_id = predecessor_account_id();
 
-        if self.accounts.get(&account_id).is_some() {
+        if self.internal_get_account(&account_id).is_some() {
             panic!("Account already registered");
         }
 
@@ -72,7 +129,7 @@ impl Hockey {
                     .try_to_vec().unwrap()),
         };
 
-        self.accounts.insert(&account_id, &account);
+        self.internal_save_account(&account_id, account);
     }
 
     pub fn set_team_logo(
@@ -83,38 +140,38 @@ impl Hockey {
     pub fn remove_friend(&mut self, friend_id: AccountId) {
         let account_id = predecessor_account_id();
         
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         assert_ne!(account_id.clone(), friend_id.clone(), "Wrong friend id");
 
         account.friends.remove(&friend_id);
         friend.friends.remove(&account_id);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 
     pub fn send_friend_request(&mut self, friend_id: AccountId) {
         let account_id = predecessor_account_id();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         assert_ne!(account_id.clone(), friend_id.clone(), "Wrong friend id");
 
         account.sent_friend_requests.insert(&friend_id);
         friend.friend_requests_received.insert(&account_id);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 
     pub fn accept_friend_request(&mut self, friend_id: AccountId) {
         let account_id = predecessor_account_id();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         if !account.friend_requests_received.remove(&friend_id) {
             panic!("Friend id not found")
@@ -127,73 +184,123 @@ impl Hockey {
         account.friends.insert(&friend_id);
         friend.friends.insert(&account_id);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 
     pub fn decline_friend_request(&mut self, friend_id: AccountId) {
         let account_id = predecessor_account_id();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         account.friend_requests_received.remove(&friend_id);
         account.sent_friend_requests.remove(&friend_id);
         friend.sent_friend_requests.remove(&account_id);
         friend.friend_requests_received.remove(&account_id);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 
+    /// The attached deposit is the stake. Without it the game is friendly
     #[payable]
-    pub fn send_request_play(&mut self, friend_id: AccountId) {
-        let account_id = predecessor_account_id();
-        let deposit = attached_deposit();
+    pub fn send_request_play(&mut self, friend_id: AccountId, rules: Option<VMatchRules>) {
+        self.internal_send_request_play(predecessor_account_id(), friend_id, rules, attached_deposit(), None);
+    }
+
+    #[payable]
+    pub fn accept_request_play(&mut self, friend_id: AccountId) -> Promise {
+        self.internal_accept_request_play(predecessor_account_id(), friend_id, attached_deposit(), None)
+    }
+
+    pub(crate) fn internal_send_request_play(
+        &mut self,
+        account_id: AccountId,
+        friend_id: AccountId,
+        rules: Option<VMatchRules>,
+        deposit: Balance,
+        token_id: Option<AccountId>
+    ) {
+        self.assert_not_paused();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         assert_ne!(account_id.clone(), friend_id.clone(), "Wrong friend id");
 
+        let request_key = (account_id.clone(), friend_id.clone());
+        match rules {
+            Some(rules) => {
+                MatchRules::from(rules.clone()).assert_valid();
+                self.play_request_rules.insert(&request_key, &rules);
+            },
+            None => {
+                self.play_request_rules.remove(&request_key);
+            }
+        }
+
+        // A new request replaces the previous one, whose stake is returned
+        if let Some(previous_deposit) = account.sent_requests_play.get(&friend_id) {
+            let previous_token_id = self.play_request_tokens.get(&request_key);
+            self.internal_credit(&account_id, &previous_token_id, previous_deposit);
+        }
+
+        match token_id {
+            Some(token_id) => self.play_request_tokens.insert(&request_key, &token_id),
+            None => self.play_request_tokens.remove(&request_key),
+        };
+
         account.sent_requests_play.insert(&friend_id, &deposit);
         friend.requests_play_received.insert(&account_id, &deposit);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 
-    pub fn accept_request_play(&mut self, friend_id: AccountId) -> Promise {
-        let account_id = predecessor_account_id();
-        let deposit = attached_deposit();
+    pub(crate) fn internal_accept_request_play(
+        &mut self,
+        account_id: AccountId,
+        friend_id: AccountId,
+        deposit: Balance,
+        token_id: Option<AccountId>
+    ) -> Promise {
+        self.assert_not_paused();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
 
         let friend_deposit = friend.sent_requests_play.get(&account_id).expect("Request to play not found");
 
         assert_eq!(deposit, friend_deposit, "Wrong deposit");
 
+        let request_key = (friend_id.clone(), account_id.clone());
+        assert!(self.play_request_tokens.get(&request_key) == token_id, "Wrong token");
+        self.play_request_tokens.remove(&request_key);
+
         if account.requests_play_received.remove(&friend_id).is_none() {
             panic!("Friend id not found");
         }
 
-        if !friend.sent_requests_play.remove(&account_id).is_none() {
+        if friend.sent_requests_play.remove(&account_id).is_none() {
             panic!("Account id not found");
         }
 
         self.internal_check_if_has_game_started(&account_id);
         self.internal_check_if_has_game_started(&friend_id);
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
 
         let config = GameConfig {
             deposit: Some(deposit),
-            opponent_id: Some(friend_id.clone())
+            opponent_id: Some(friend_id.clone()),
+            rules: self.play_request_rules.remove(&(friend_id.clone(), account_id.clone())),
+            waiting_since: None,
+            token_id,
         };
 
-        ext_manage_team::ext(AccountId::new_unchecked(NFT_CONTRACT.parse().unwrap()))
+        ext_manage_team::ext(self.contract_config.nft_contract_id.clone())
             .with_static_gas(Gas(100_000_000_000_000))
             .get_teams(account_id.clone(), friend_id.clone())
             .then(
@@ -212,8 +319,8 @@ impl Hockey {
     ) -> Option<Game> {
         if call_result.is_err() {
             log!("The team is incomplete");
-            Promise::new(account_id).transfer(config.deposit.unwrap());
-            Promise::new(config.opponent_id.unwrap()).transfer(config.deposit.unwrap());
+            self.internal_credit(&account_id, &config.token_id, config.deposit.unwrap());
+            self.internal_credit(&config.opponent_id.clone().unwrap(), &config.token_id, config.deposit.unwrap());
             return None;
         }
         let teams = call_result.unwrap();
@@ -223,24 +330,28 @@ impl Hockey {
     pub fn decline_request_play(&mut self, friend_id: AccountId) {
         let account_id = predecessor_account_id();
 
-        let mut account = self.accounts.get(&account_id).expect("You are not registered");
-        let mut friend = self.accounts.get(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+        let mut account = self.internal_get_account(&account_id).expect("You are not registered");
+        let mut friend = self.internal_get_account(&friend_id).expect(&format!("Account not found {}", friend_id.clone()));
+
+        self.play_request_rules.remove(&(friend_id.clone(), account_id.clone()));
+        self.play_request_rules.remove(&(account_id.clone(), friend_id.clone()));
 
-        account.requests_play_received.remove(&friend_id);
-        account.sent_requests_play.remove(&friend_id);
+        let account_token_id = self.play_request_tokens.remove(&(account_id.clone(), friend_id.clone()));
+        let friend_token_id = self.play_request_tokens.remove(&(friend_id.clone(), account_id.clone()));
 
-        if let Some(deposit) = friend.requests_play_received.remove(&account_id) {
-            account.sent_requests_play.remove(&friend_id);
-            Promise::new(account_id.clone()).transfer(deposit);
+        // The request sent by the account is cancelled, the one received from the friend is declined
+        if let Some(deposit) = account.sent_requests_play.remove(&friend_id) {
+            friend.requests_play_received.remove(&account_id);
+            self.internal_credit(&account_id, &account_token_id, deposit);
         }
 
-        if let Some(deposit) = account.requests_play_received.remove(&account_id) {
+        if let Some(deposit) = account.requests_play_received.remove(&friend_id) {
             friend.sent_requests_play.remove(&account_id);
-            Promise::new(friend_id.clone()).transfer(deposit);
+            self.internal_credit(&friend_id, &friend_token_id, deposit);
         }
 
-        self.accounts.insert(&account_id, &account);
-        self.accounts.insert(&friend_id, &friend);
+        self.internal_save_account(&account_id, account);
+        self.internal_save_account(&friend_id, friend);
     }
 }
 